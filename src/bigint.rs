@@ -0,0 +1,620 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+const LIMB_BITS: u32 = u32::BITS;
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid big integer literal")
+    }
+}
+
+impl Error for ParseBigIntError {}
+
+// little-endian base 2^32 limbs without trailing zero limbs, so zero has no limbs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [low] => Some(*low as u64),
+            [low, high] => Some((*high as u64) << LIMB_BITS | *low as u64),
+            _ => None,
+        }
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => (self.limbs.len() as u64 - 1) * LIMB_BITS as u64 + (LIMB_BITS - top.leading_zeros()) as u64,
+            None => 0,
+        }
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let mut difference = *limb as i64 - other.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = 0;
+            if difference < 0 {
+                difference += 1 << LIMB_BITS;
+                borrow = 1;
+            }
+            limbs.push(difference as u32);
+        }
+
+        Some(Self::from_limbs(limbs))
+    }
+
+    // panics on division by zero, like the primitive types
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        if let [small] = divisor.limbs.as_slice() {
+            let (quotient, remainder) = self.div_rem_small(*small);
+            return (quotient, BigUint::from(remainder));
+        }
+
+        if *self < *divisor {
+            return (BigUint::zero(), self.clone());
+        }
+
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+        for bit in (0..self.bits()).rev() {
+            remainder.shift_left_one();
+            if self.bit(bit) {
+                remainder.set_lowest_bit();
+            }
+
+            if remainder >= *divisor {
+                remainder = remainder.checked_sub(divisor).unwrap();
+                quotient[(bit / LIMB_BITS as u64) as usize] |= 1 << (bit % LIMB_BITS as u64);
+            }
+        }
+
+        (Self::from_limbs(quotient), remainder)
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigUint {
+        let mut base = self.clone();
+        let mut result = BigUint::from(1u32);
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exponent >>= 1;
+        }
+
+        result
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        Self { limbs }
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+
+        for (i, limb) in self.limbs.iter().enumerate().rev() {
+            let current = remainder << LIMB_BITS | *limb as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+
+        (Self::from_limbs(quotient), remainder as u32)
+    }
+
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> LIMB_BITS;
+        }
+
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        *self = Self::from_limbs(std::mem::take(&mut self.limbs));
+    }
+
+    fn bit(&self, bit: u64) -> bool {
+        let limb = self.limbs[(bit / LIMB_BITS as u64) as usize];
+        limb >> (bit % LIMB_BITS as u64) & 1 == 1
+    }
+
+    fn shift_left_one(&mut self) {
+        let mut carry = 0;
+        for limb in self.limbs.iter_mut() {
+            let next_carry = *limb >> (LIMB_BITS - 1);
+            *limb = *limb << 1 | carry;
+            carry = next_carry;
+        }
+
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    fn set_lowest_bit(&mut self) {
+        match self.limbs.first_mut() {
+            Some(limb) => *limb |= 1,
+            None => self.limbs.push(1),
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from_limbs(vec![value as u32, (value >> LIMB_BITS) as u32])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        Self::from_limbs(vec![value])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('+').unwrap_or(s);
+        if s.is_empty() || !s.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut result = BigUint::zero();
+        let first_chunk = match s.len() % DECIMAL_CHUNK_DIGITS {
+            0 => DECIMAL_CHUNK_DIGITS,
+            rest => rest,
+        };
+
+        let (head, tail) = s.split_at(first_chunk);
+        result.mul_add_small(1, head.parse().unwrap());
+        for chunk in tail.as_bytes().chunks(DECIMAL_CHUNK_DIGITS) {
+            let chunk = std::str::from_utf8(chunk).unwrap().parse().unwrap();
+            result.mul_add_small(DECIMAL_CHUNK, chunk);
+        }
+
+        Ok(result)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem_small(DECIMAL_CHUNK);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+
+        let mut text = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            text.push_str(&format!("{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS));
+        }
+
+        f.pad_integral(true, "", &text)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> Self::Output {
+        let (longer, shorter) = if self.limbs.len() >= rhs.limbs.len() { (self, rhs) } else { (rhs, self) };
+
+        let mut limbs = Vec::with_capacity(longer.limbs.len() + 1);
+        let mut carry = 0u64;
+        for (i, limb) in longer.limbs.iter().enumerate() {
+            let sum = *limb as u64 + shorter.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> LIMB_BITS;
+        }
+        limbs.push(carry as u32);
+
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> Self::Output {
+        self.checked_sub(rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in rhs.limbs.iter().enumerate() {
+                let current = limbs[i + j] as u64 + *a as u64 * *b as u64 + carry;
+                limbs[i + j] = current as u32;
+                carry = current >> LIMB_BITS;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = self.magnitude.to_u64()?;
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    // quotient is truncated toward zero and the remainder has the sign of `self`, like i64
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        let (quotient, remainder) = self.magnitude.div_rem(&divisor.magnitude);
+        (
+            Self::from_parts(self.negative != divisor.negative, quotient),
+            Self::from_parts(self.negative, remainder),
+        )
+    }
+
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::from_parts(false, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::from_parts(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(rest) if !rest.starts_with('+') => Ok(Self::from_parts(true, rest.parse()?)),
+            Some(_) => Err(ParseBigIntError),
+            None => Ok(Self::from_parts(false, s.parse()?)),
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> Self::Output {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+
+        if self.magnitude >= rhs.magnitude {
+            BigInt::from_parts(self.negative, &self.magnitude - &rhs.magnitude)
+        } else {
+            BigInt::from_parts(rhs.negative, &rhs.magnitude - &self.magnitude)
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> Self::Output {
+        BigInt::from_parts(self.negative != rhs.negative, &self.magnitude * &rhs.magnitude)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &BigInt) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &BigInt) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+// by-value operators and assignment forms on top of the by-reference ones
+macro_rules! forward_ops {
+    ($type:ty) => {
+        forward_ops!($type, Add, add, AddAssign, add_assign);
+        forward_ops!($type, Sub, sub, SubAssign, sub_assign);
+        forward_ops!($type, Mul, mul, MulAssign, mul_assign);
+
+        impl Div<$type> for $type {
+            type Output = $type;
+
+            fn div(self, rhs: $type) -> Self::Output {
+                &self / &rhs
+            }
+        }
+
+        impl Rem<$type> for $type {
+            type Output = $type;
+
+            fn rem(self, rhs: $type) -> Self::Output {
+                &self % &rhs
+            }
+        }
+
+        impl Sum for $type {
+            fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+                iter.fold(<$type>::zero(), |acc, value| acc + value)
+            }
+        }
+
+        impl<'a> Sum<&'a $type> for $type {
+            fn sum<I: Iterator<Item=&'a $type>>(iter: I) -> Self {
+                iter.fold(<$type>::zero(), |acc, value| &acc + value)
+            }
+        }
+    };
+    ($type:ty, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait<$type> for $type {
+            type Output = $type;
+
+            fn $method(self, rhs: $type) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $trait<&$type> for $type {
+            type Output = $type;
+
+            fn $method(self, rhs: &$type) -> Self::Output {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $assign_trait<&$type> for $type {
+            fn $assign_method(&mut self, rhs: &$type) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+
+        impl $assign_trait<$type> for $type {
+            fn $assign_method(&mut self, rhs: $type) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+    };
+}
+
+forward_ops!(BigUint);
+forward_ops!(BigInt);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    fn signed(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let text = "123456789012345678901234567890";
+
+        assert_eq!(text, big(text).to_string());
+        assert_eq!("0", big("000").to_string());
+        assert_eq!("1000000000", big("1000000000").to_string());
+        assert_eq!(Err(ParseBigIntError), "12a".parse::<BigUint>());
+        assert_eq!(Err(ParseBigIntError), "".parse::<BigUint>());
+        assert_eq!("-42", signed("-42").to_string());
+        assert_eq!("0", signed("-0").to_string());
+        assert_eq!("   -7", format!("{:>5}", signed("-7")));
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let max = BigUint::from(u64::MAX);
+
+        assert_eq!(big("18446744073709551616"), &max + &BigUint::from(1u32));
+        assert_eq!(max, big("18446744073709551616") - BigUint::from(1u32));
+        assert_eq!(None, BigUint::from(1u32).checked_sub(&max));
+    }
+
+    #[test]
+    fn test_mul() {
+        let max = BigUint::from(u64::MAX);
+
+        assert_eq!(big("340282366920938463426481119284349108225"), &max * &max);
+        assert_eq!(BigUint::zero(), &max * &BigUint::zero());
+        assert_eq!(big("1267650600228229401496703205376"), BigUint::from(2u32).pow(100));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let dividend = big("340282366920938463426481119284349108230");
+        let divisor = BigUint::from(u64::MAX);
+
+        assert_eq!((divisor.clone(), BigUint::from(5u32)), dividend.div_rem(&divisor));
+        assert_eq!((big("34028236692093846342648111928434910823"), BigUint::zero()), dividend.div_rem(&BigUint::from(10u32)));
+        assert_eq!((BigUint::zero(), BigUint::from(5u32)), BigUint::from(5u32).div_rem(&divisor));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(big("18446744073709551616") > BigUint::from(u64::MAX));
+        assert!(signed("-5") < signed("-4"));
+        assert!(signed("-5") < signed("3"));
+        assert_eq!(Some(u64::MAX), BigUint::from(u64::MAX).to_u64());
+        assert_eq!(None, big("18446744073709551616").to_u64());
+        assert_eq!(Some(i64::MIN), BigInt::from(i64::MIN).to_i64());
+    }
+
+    #[test]
+    fn test_signed_arithmetic() {
+        assert_eq!(signed("-2"), signed("3") + signed("-5"));
+        assert_eq!(signed("8"), signed("3") - signed("-5"));
+        assert_eq!(signed("-15"), signed("3") * signed("-5"));
+        assert_eq!((signed("-2"), signed("-1")), signed("-7").div_rem(&signed("3")));
+        assert_eq!((signed("-2"), signed("1")), signed("7").div_rem(&signed("-3")));
+    }
+
+    #[test]
+    fn test_sum() {
+        let values = [BigUint::from(u64::MAX), BigUint::from(u64::MAX), BigUint::from(2u32)];
+
+        assert_eq!(big("36893488147419103232"), values.iter().sum());
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use crate::multimap::{self, MultiMap, ValuesGroupIter};
+
+// MultiMap with a value -> keys index kept alongside, so reverse lookups and in-degrees are O(1)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BiMultiMap<K: Eq + Hash, V: Eq + Hash> {
+    forward: MultiMap<K, V>,
+    reverse: MultiMap<V, K>,
+}
+
+impl<K: Eq + Hash + Clone, V: Eq + Hash + Clone> BiMultiMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            forward: MultiMap::new(),
+            reverse: MultiMap::new(),
+        }
+    }
+
+    // returns false if the pair was already there
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        if self.forward.contains(&key, &value) {
+            return false;
+        }
+
+        self.reverse.insert(value.clone(), key.clone());
+        self.forward.insert(key, value);
+        true
+    }
+
+    pub fn remove(&mut self, key: &K, value: &V) -> bool {
+        if !self.forward.contains(key, value) {
+            return false;
+        }
+
+        self.forward.remove(key, value);
+        self.reverse.remove(value, key);
+        true
+    }
+
+    pub fn remove_key(&mut self, key: &K) -> Option<HashSet<V>> {
+        let values = self.forward.remove_key(key)?;
+        for value in &values {
+            self.reverse.remove(value, key);
+        }
+        Some(values)
+    }
+
+    pub fn remove_value(&mut self, value: &V) -> Option<HashSet<K>> {
+        let keys = self.reverse.remove_key(value)?;
+        for key in &keys {
+            self.forward.remove(key, value);
+        }
+        Some(keys)
+    }
+
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, mut predicate: F) {
+        self.forward.retain(|key, value| predicate(key, value));
+        self.reverse.retain(|value, key| self.forward.contains(key, value));
+    }
+
+    pub fn contains(&self, key: &K, value: &V) -> bool {
+        self.forward.contains(key, value)
+    }
+
+    pub fn get(&self, key: &K) -> ValuesGroupIter<'_, V> {
+        self.forward.get_or_empty(key)
+    }
+
+    pub fn keys_for(&self, value: &V) -> ValuesGroupIter<'_, K> {
+        self.reverse.get_or_empty(value)
+    }
+
+    pub fn out_degree(&self, key: &K) -> usize {
+        self.forward.get(key).map_or(0, |values| values.len())
+    }
+
+    pub fn in_degree(&self, value: &V) -> usize {
+        self.reverse.get(value).map_or(0, |keys| keys.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    pub fn iter(&self) -> multimap::Iter<'_, K, V> {
+        self.forward.iter()
+    }
+
+    pub fn forward(&self) -> &MultiMap<K, V> {
+        &self.forward
+    }
+
+    pub fn reverse(&self) -> &MultiMap<V, K> {
+        &self.reverse
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Eq + Hash + Clone> Default for BiMultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Eq + Hash + Clone, const N: usize> From<[(K, V); N]> for BiMultiMap<K, V> {
+    fn from(keys_values: [(K, V); N]) -> Self {
+        keys_values.into_iter().collect()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Eq + Hash + Clone> FromIterator<(K, V)> for BiMultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        let mut map = BiMultiMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_lookup() {
+        let map = BiMultiMap::from([
+            (11, 22),
+            (33, 22),
+            (11, 44),
+        ]);
+
+        let mut keys = map.keys_for(&22).copied().collect::<Vec<_>>();
+        keys.sort();
+
+        assert_eq!(vec![11, 33], keys);
+        assert_eq!(None, map.keys_for(&11).next());
+        assert_eq!(2, map.in_degree(&22));
+        assert_eq!(0, map.in_degree(&11));
+        assert_eq!(2, map.out_degree(&11));
+        assert_eq!(3, map.len());
+    }
+
+    #[test]
+    fn test_remove_keeps_both_sides() {
+        let mut map = BiMultiMap::from([
+            (11, 22),
+            (33, 22),
+            (11, 44),
+        ]);
+
+        assert!(map.remove(&33, &22));
+        assert!(!map.remove(&33, &22));
+        assert_eq!(1, map.in_degree(&22));
+
+        assert_eq!(Some(HashSet::from([22, 44])), map.remove_key(&11));
+        assert_eq!(0, map.in_degree(&22));
+        assert!(map.is_empty());
+        assert!(map.reverse().is_empty());
+    }
+
+    #[test]
+    fn test_retain_remove_value() {
+        let mut map = BiMultiMap::from([
+            (11, 22),
+            (33, 22),
+            (11, 44),
+            (55, 66),
+        ]);
+
+        map.retain(|key, _| *key != 33);
+        assert_eq!(Some(HashSet::from([55])), map.remove_value(&66));
+
+        assert_eq!(BiMultiMap::from([(11, 22), (11, 44)]), map);
+    }
+}
@@ -0,0 +1,189 @@
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            words: vec![0; (width * height).div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_fit(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    // returns true if the bit was not set before
+    pub fn set(&mut self, x: usize, y: usize) -> bool {
+        let (word, mask) = self.locate(x, y);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] |= mask;
+        !was_set
+    }
+
+    pub fn test(&self, x: usize, y: usize) -> bool {
+        if !self.is_fit(x, y) {
+            return false;
+        }
+
+        let (word, mask) = self.locate(x, y);
+        self.words[word] & mask != 0
+    }
+
+    // returns true if the bit was set before
+    pub fn clear(&mut self, x: usize, y: usize) -> bool {
+        let (word, mask) = self.locate(x, y);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    pub fn clear_all(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        self.words.iter_mut().zip(&other.words).for_each(|(word, other)| *word |= other);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        self.words.iter_mut().zip(&other.words).for_each(|(word, other)| *word &= other);
+    }
+
+    pub fn union(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    pub fn intersection(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            grid: self,
+            word_index: 0,
+            current_word: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    fn locate(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(self.is_fit(x, y), "({x}, {y}) is out of {}x{} grid", self.width, self.height);
+        let index = y * self.width + x;
+        (index / WORD_BITS, 1 << (index % WORD_BITS))
+    }
+
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert_eq!((self.width, self.height), (other.width, other.height), "grid sizes differ");
+    }
+}
+
+pub struct Iter<'a> {
+    grid: &'a BitGrid,
+    word_index: usize,
+    current_word: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current_word == 0 {
+            self.word_index += 1;
+            self.current_word = *self.grid.words.get(self.word_index)?;
+        }
+
+        let bit = self.current_word.trailing_zeros() as usize;
+        self.current_word &= self.current_word - 1;
+
+        let index = self.word_index * WORD_BITS + bit;
+        Some((index % self.grid.width, index / self.grid.width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_test_clear() {
+        let mut grid = BitGrid::new(10, 10);
+
+        assert!(grid.set(3, 7));
+        assert!(!grid.set(3, 7));
+        assert!(grid.test(3, 7));
+        assert!(!grid.test(7, 3));
+        assert!(!grid.test(10, 0));
+
+        assert!(grid.clear(3, 7));
+        assert!(!grid.clear(3, 7));
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_count_ones() {
+        let mut grid = BitGrid::new(9, 9);
+
+        grid.set(0, 0);
+        grid.set(8, 8);
+        grid.set(1, 7);
+        grid.set(1, 7);
+
+        assert_eq!(3, grid.count_ones());
+    }
+
+    #[test]
+    fn test_union_intersection() {
+        let mut a = BitGrid::new(5, 5);
+        let mut b = BitGrid::new(5, 5);
+
+        a.set(0, 0);
+        a.set(1, 1);
+        b.set(1, 1);
+        b.set(4, 4);
+
+        assert_eq!(vec![(0, 0), (1, 1), (4, 4)], a.union(&b).iter().collect::<Vec<_>>());
+        assert_eq!(vec![(1, 1)], a.intersection(&b).iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut grid = BitGrid::new(13, 11);
+        let points = [(0, 0), (12, 4), (0, 5), (7, 9), (12, 10)];
+
+        for (x, y) in points {
+            grid.set(x, y);
+        }
+
+        assert_eq!(points.to_vec(), grid.iter().collect::<Vec<_>>());
+        assert_eq!(None, BitGrid::new(0, 0).iter().next());
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::{hash_map, HashMap};
+use std::hash::Hash;
+
+// MultiMap that keeps the multiplicity of every (key, value) pair instead of deduplicating it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountedMultiMap<K: Eq + Hash, V: Eq + Hash> {
+    map: HashMap<K, HashMap<V, usize>>,
+    total_len: usize,
+}
+
+impl<K: Eq + Hash, V: Eq + Hash> CountedMultiMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            map: Default::default(),
+            total_len: 0,
+        }
+    }
+
+    // returns the new count of the pair
+    pub fn insert(&mut self, key: K, value: V) -> usize {
+        let count = self.map.entry(key).or_default().entry(value).or_default();
+        *count += 1;
+        self.total_len += 1;
+        *count
+    }
+
+    // returns the remaining count of the pair
+    pub fn remove(&mut self, key: &K, value: &V) -> usize {
+        let Some(values) = self.map.get_mut(key) else {
+            return 0;
+        };
+        let Some(count) = values.get_mut(value) else {
+            return 0;
+        };
+
+        *count -= 1;
+        self.total_len -= 1;
+        let remaining = *count;
+
+        if remaining == 0 {
+            values.remove(value);
+            if values.is_empty() {
+                self.map.remove(key);
+            }
+        }
+
+        remaining
+    }
+
+    pub fn count(&self, key: &K, value: &V) -> usize {
+        self.map.get(key).and_then(|values| values.get(value)).copied().unwrap_or(0)
+    }
+
+    pub fn contains(&self, key: &K, value: &V) -> bool {
+        self.count(key, value) > 0
+    }
+
+    // number of pairs, every repetition included
+    pub fn total_len(&self) -> usize {
+        self.total_len
+    }
+
+    // sum of the counts of all values under the key
+    pub fn key_count(&self, key: &K) -> usize {
+        self.map.get(key).map_or(0, |values| values.values().sum())
+    }
+
+    pub fn get(&self, key: &K) -> Option<ValuesGroupIter<'_, V>> {
+        self.map.get(key).map(|values| ValuesGroupIter { iter: values.iter() })
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            map_iter: self.map.iter(),
+            current: None,
+        }
+    }
+
+    pub fn grouped_values(&self) -> GroupedValues<'_, K, V> {
+        GroupedValues {
+            map_iter: self.map.iter(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash> Default for CountedMultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, const N: usize> From<[(K, V); N]> for CountedMultiMap<K, V> {
+    fn from(keys_values: [(K, V); N]) -> Self {
+        keys_values.into_iter().collect()
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash> FromIterator<(K, V)> for CountedMultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        let mut map = CountedMultiMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+pub struct Iter<'a, K: 'a, V: 'a> {
+    map_iter: hash_map::Iter<'a, K, HashMap<V, usize>>,
+    current: Option<(&'a K, hash_map::Iter<'a, V, usize>)>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, values_iter)) = &mut self.current {
+                if let Some((value, count)) = values_iter.next() {
+                    return Some((key, value, *count));
+                }
+            }
+
+            let (key, values) = self.map_iter.next()?;
+            self.current = Some((key, values.iter()));
+        }
+    }
+}
+
+pub struct ValuesGroupIter<'a, V: 'a> {
+    iter: hash_map::Iter<'a, V, usize>,
+}
+
+impl<'a, V: 'a> Iterator for ValuesGroupIter<'a, V> {
+    type Item = (&'a V, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(value, count)| (value, *count))
+    }
+}
+
+pub struct GroupedValues<'a, K: 'a, V: 'a> {
+    map_iter: hash_map::Iter<'a, K, HashMap<V, usize>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for GroupedValues<'a, K, V> {
+    type Item = (&'a K, ValuesGroupIter<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.map_iter.next().map(|(key, values)| (key, ValuesGroupIter { iter: values.iter() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_count() {
+        let mut map = CountedMultiMap::new();
+
+        assert_eq!(1, map.insert(11, 22));
+        assert_eq!(2, map.insert(11, 22));
+        assert_eq!(1, map.insert(11, 33));
+
+        assert_eq!(2, map.count(&11, &22));
+        assert_eq!(0, map.count(&11, &44));
+        assert_eq!(3, map.key_count(&11));
+        assert_eq!(3, map.total_len());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = CountedMultiMap::from([
+            (11, 22),
+            (11, 22),
+            (22, 44),
+        ]);
+
+        assert_eq!(1, map.remove(&11, &22));
+        assert_eq!(0, map.remove(&22, &44));
+        assert_eq!(0, map.remove(&22, &44));
+
+        assert_eq!(CountedMultiMap::from([(11, 22)]), map);
+        assert_eq!(1, map.total_len());
+        assert!(!map.contains(&22, &44));
+    }
+
+    #[test]
+    fn test_iter() {
+        let map = CountedMultiMap::from([
+            (11, 22),
+            (11, 33),
+            (11, 22),
+            (22, 44),
+        ]);
+
+        let mut entries = map.iter().map(|(key, value, count)| (*key, *value, count)).collect::<Vec<_>>();
+        entries.sort();
+
+        assert_eq!(vec![(11, 22, 2), (11, 33, 1), (22, 44, 1)], entries);
+
+        let mut group = map.get(&11).unwrap().collect::<Vec<_>>();
+        group.sort();
+        assert_eq!(vec![(&22, 2), (&33, 1)], group);
+    }
+}
@@ -0,0 +1,179 @@
+// `mu` is the index of the first state on the cycle, `lambda` is the cycle length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Cycle(Cycle),
+    // the step function returned None after this many successful steps
+    Terminated(usize),
+}
+
+impl Outcome {
+    pub fn cycle(self) -> Option<Cycle> {
+        match self {
+            Outcome::Cycle(cycle) => Some(cycle),
+            Outcome::Terminated(_) => None,
+        }
+    }
+}
+
+pub fn floyd<S: PartialEq + Clone, F: Fn(&S) -> Option<S>>(start: S, step: F) -> Outcome {
+    let mut hare = match floyd_meeting_point(&start, &step) {
+        Ok(hare) => hare,
+        Err(length) => return Outcome::Terminated(length),
+    };
+
+    let mut mu = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise).unwrap();
+        hare = step(&hare).unwrap();
+        mu += 1;
+    }
+
+    let mut lambda = 1;
+    hare = step(&tortoise).unwrap();
+    while tortoise != hare {
+        hare = step(&hare).unwrap();
+        lambda += 1;
+    }
+
+    Outcome::Cycle(Cycle { mu, lambda })
+}
+
+// stops at the first meeting point, without working out where the cycle starts or how long it is
+pub fn has_cycle<S: PartialEq + Clone, F: Fn(&S) -> Option<S>>(start: S, step: F) -> bool {
+    floyd_meeting_point(&start, &step).is_ok()
+}
+
+// a state on the cycle, or the length of the sequence if it ends
+fn floyd_meeting_point<S: PartialEq + Clone, F: Fn(&S) -> Option<S>>(start: &S, step: &F) -> Result<S, usize> {
+    let Some(mut tortoise) = step(start) else {
+        return Err(0);
+    };
+    let Some(mut hare) = step(&tortoise) else {
+        return Err(1);
+    };
+    let mut hare_steps = 2;
+
+    while tortoise != hare {
+        // the tortoise walks states the hare has already produced
+        tortoise = step(&tortoise).unwrap();
+        for _ in 0..2 {
+            let Some(next) = step(&hare) else {
+                return Err(hare_steps);
+            };
+            hare = next;
+            hare_steps += 1;
+        }
+    }
+
+    Ok(hare)
+}
+
+pub fn brent<S: PartialEq + Clone, F: Fn(&S) -> Option<S>>(start: S, step: F) -> Outcome {
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut hare_steps = 1;
+
+    let mut tortoise = start.clone();
+    let Some(mut hare) = step(&start) else {
+        return Outcome::Terminated(0);
+    };
+
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+
+        let Some(next) = step(&hare) else {
+            return Outcome::Terminated(hare_steps);
+        };
+        hare = next;
+        hare_steps += 1;
+        lambda += 1;
+    }
+
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..lambda {
+        hare = step(&hare).unwrap();
+    }
+
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise).unwrap();
+        hare = step(&hare).unwrap();
+        mu += 1;
+    }
+
+    Outcome::Cycle(Cycle { mu, lambda })
+}
+
+// state after `steps` steps, skipping whole laps of the cycle; None if the sequence ends earlier
+pub fn state_after<S: PartialEq + Clone, F: Fn(&S) -> Option<S>>(start: S, step: F, steps: usize) -> Option<S> {
+    let steps = match brent(start.clone(), &step) {
+        Outcome::Cycle(Cycle { mu, lambda }) if steps >= mu => mu + (steps - mu) % lambda,
+        Outcome::Terminated(length) if steps > length => return None,
+        _ => steps,
+    };
+
+    let mut state = start;
+    for _ in 0..steps {
+        state = step(&state)?;
+    }
+
+    Some(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn rho(state: &u32) -> Option<u32> {
+        Some(if *state == 5 { 2 } else { state + 1 })
+    }
+
+    fn finite(state: &u32) -> Option<u32> {
+        (*state < 4).then_some(state + 1)
+    }
+
+    #[test]
+    fn test_floyd() {
+        assert_eq!(Outcome::Cycle(Cycle { mu: 2, lambda: 4 }), floyd(0, rho));
+        assert_eq!(Outcome::Cycle(Cycle { mu: 0, lambda: 1 }), floyd(7, |s: &u32| Some(*s)));
+        assert_eq!(Outcome::Terminated(4), floyd(0, finite));
+        assert_eq!(Outcome::Terminated(0), floyd(4, finite));
+    }
+
+    #[test]
+    fn test_has_cycle() {
+        assert!(has_cycle(0, rho));
+        assert!(has_cycle(7, |s: &u32| Some(*s)));
+        assert!(!has_cycle(0, finite));
+        assert!(!has_cycle(4, finite));
+    }
+
+    #[test]
+    fn test_brent() {
+        assert_eq!(Outcome::Cycle(Cycle { mu: 2, lambda: 4 }), brent(0, rho));
+        assert_eq!(Outcome::Cycle(Cycle { mu: 0, lambda: 1 }), brent(7, |s: &u32| Some(*s)));
+        assert_eq!(Outcome::Terminated(4), brent(0, finite));
+        assert_eq!(Outcome::Terminated(1), brent(3, finite));
+    }
+
+    #[test]
+    fn test_state_after() {
+        assert_eq!(Some(1), state_after(0, rho, 1));
+        assert_eq!(Some(5), state_after(0, rho, 1_000_000_001));
+        assert_eq!(Some(4), state_after(0, finite, 4));
+        assert_eq!(None, state_after(0, finite, 5));
+    }
+}
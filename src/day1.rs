@@ -1,9 +1,10 @@
 #![allow(dead_code)]
 
 use std::iter::zip;
+use crate::parse::{self, ParseError};
 
 pub fn day(input: &str) -> u32 {
-    let (mut list1, mut list2) = parse_lists(input);
+    let (mut list1, mut list2) = parse_lists(input).unwrap();
 
     list1.sort();
     list2.sort();
@@ -17,7 +18,7 @@ pub fn day(input: &str) -> u32 {
 }
 
 pub fn day_2(input: &str) -> u32 {
-    let (list1, list2) = parse_lists(input);
+    let (list1, list2) = parse_lists(input).unwrap();
     
     // println!("{:?}", list1);
     // println!("{:?}", list2);
@@ -29,12 +30,13 @@ pub fn day_2(input: &str) -> u32 {
     sum
 }
 
-fn parse_lists(input: &str) -> (Vec<u32>, Vec<u32>) {
-    let lines = input.lines().filter(|line| !line.is_empty());
-    lines.map(|line| line.split_whitespace())
-        .map(|split| split.map(|id_str| id_str.parse::<u32>().unwrap()))
-        .map(|mut numbers| (numbers.next().unwrap(), numbers.next().unwrap()))
-        .unzip()
+fn parse_lists(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    parse::lines(input)
+        .map(|line| match line.fields::<u32>()?[..] {
+            [number1, number2] => Ok((number1, number2)),
+            ref numbers => Err(line.error(1, format!("expected 2 numbers, found {}", numbers.len()))),
+        })
+        .collect()
 }
 
 
@@ -67,4 +69,12 @@ mod tests {
 
         assert_eq!(31, day_2(input));
     }
+
+    #[test]
+    fn test_parse_lists_error() {
+        let error = parse_lists("3   4\n4\n").unwrap_err();
+
+        assert_eq!((2, 1), (error.line, error.column));
+        assert_eq!("expected 2 numbers, found 1", error.message);
+    }
 }
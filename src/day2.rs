@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+use crate::parse;
+
 pub fn day(input: &str) -> u32 {
     let reports = parse_reports(input);
     // println!("{:?}", reports);
@@ -29,12 +31,9 @@ pub fn day_2(input: &str) -> u32 {
 }
 
 fn parse_reports(input: &str) -> Vec<Vec<u32>> {
-    let lines = input.lines().filter(|line| !line.is_empty());
-    lines.map(
-        |line| line.split_whitespace()
-            .map(|num_str| num_str.parse().unwrap())
-            .collect()
-    ).collect()
+    parse::lines(input)
+        .map(|line| line.fields().unwrap())
+        .collect()
 }
 
 fn check_report_is_good(report: &[u32]) -> bool {
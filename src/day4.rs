@@ -3,56 +3,24 @@
 use std::borrow::Borrow;
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Mul};
+use crate::grid::GridView;
+use crate::parse;
 
 pub fn day(input: &str) -> u32 {
-    //012
-    //7X3
-    //654
-    const DIRECTIONS: [Point; 8] = [
-        Point { x: -1, y: -1 },
-        Point { x: 0, y: -1 },
-        Point { x: 1, y: -1 },
-        Point { x: 1, y: 0 },
-        Point { x: 1, y: 1 },
-        Point { x: 0, y: 1 },
-        Point { x: -1, y: 1 },
-        Point { x: -1, y: 0 },
-    ];
-
-    const STEPS: [char; 3] = [
-        'M', 'A', 'S'
-    ];
+    const WORD: &str = "XMAS";
+    const WORD_REVERSED: &str = "SAMX";
 
-    let lines: Vec<_> = input.lines().filter(|line| !line.is_empty()).collect();
+    let lines: Vec<_> = parse::lines(input).map(|line| line.text).collect();
     let field = Field::try_new(&lines).unwrap();
-    let mut sum = 0;
-
-    for y in 0..field.size {
-        for x in 0..field.size {
-            let current_point = Point::new(x as i32, y as i32);
-            if field.get(current_point) != Some('X') {
-                continue;
-            }
 
-            for direction in DIRECTIONS {
-                let mut step = 0;
-                while step < 3 {
-                    let new_point = current_point + direction * (step + 1) as i32;
-                    if field.get(new_point) != Some(STEPS[step]) {
-                        break;
-                    } else {
-                        step += 1;
-                    }
-                }
-
-                if step == 3 {
-                    sum += 1;
-                }
-            }
-        }
-    }
-
-    sum
+    [field.rows(), field.columns(), field.diagonals(), field.anti_diagonals()]
+        .into_iter()
+        .flatten()
+        .map(|line| {
+            let line = line.collect::<String>();
+            (line.matches(WORD).count() + line.matches(WORD_REVERSED).count()) as u32
+        })
+        .sum()
 }
 
 pub fn day_2(input: &str) -> u32 {
@@ -66,7 +34,7 @@ pub fn day_2(input: &str) -> u32 {
         Point { x: -1, y: 1 },
     ];
 
-    let lines: Vec<_> = input.lines().filter(|line| !line.is_empty()).collect();
+    let lines: Vec<_> = parse::lines(input).map(|line| line.text).collect();
     let field = Field::try_new(&lines).unwrap();
     let mut sum = 0;
 
@@ -141,6 +109,22 @@ impl Field {
     }
 }
 
+impl GridView for Field {
+    type Cell = char;
+
+    fn width(&self) -> usize {
+        self.size
+    }
+
+    fn height(&self) -> usize {
+        self.size
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &char {
+        &self.data[y][x]
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 struct Point {
     x: i32,
@@ -231,9 +215,9 @@ MXMXAXMASX"#;
         ];
         let field = Field::try_new(&lines).unwrap();
 
-        assert_eq!(Some('S'), field.get(&Point { x: 1, y: 0 }));
-        assert_eq!(Some('S'), field.get(&Point { x: 1, y: 1 }));
-        assert_eq!(Some('M'), field.get(&Point { x: 2, y: 2 }));
+        assert_eq!(Some('S'), field.get(Point { x: 1, y: 0 }));
+        assert_eq!(Some('S'), field.get(Point { x: 1, y: 1 }));
+        assert_eq!(Some('M'), field.get(Point { x: 2, y: 2 }));
     }
 
     #[test]
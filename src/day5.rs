@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
-use crate::multimap::MultiMap;
+use crate::bimultimap::BiMultiMap;
+use crate::parse;
 
 pub fn day(input: &str) -> u32 {
     let input = parse_input(input);
@@ -56,23 +57,16 @@ pub fn day_2(input: &str) -> u32 {
 fn sort_update(update: &[Page], rules: &Rules) -> Vec<Page> {
     let mut rules = (*rules).clone();
 
-    let first = update.iter().find(|page|
-        rules.values().all(|next| next != *page)
-    ).unwrap();
+    let first = update.iter().find(|page| rules.in_degree(page) == 0).unwrap();
 
     let mut result = vec![];
     let mut pages_to_check = vec![*first];
 
     while let Some(current_page) = pages_to_check.pop() {
         result.push(current_page);
-        let next_pages = rules.get(&current_page);
-        if let Some(next_pages) = next_pages {
-            let next_pages = next_pages.cloned().collect::<Vec<_>>();
-            for next_page in next_pages {
-                rules.remove(&current_page, &next_page);
-                if !rules.values().any(|page| *page == next_page) {
-                    pages_to_check.push(next_page);
-                }
+        for next_page in rules.remove_key(&current_page).unwrap_or_default() {
+            if rules.in_degree(&next_page) == 0 {
+                pages_to_check.push(next_page);
             }
         }
     }
@@ -81,14 +75,13 @@ fn sort_update(update: &[Page], rules: &Rules) -> Vec<Page> {
 }
 
 fn filter_relevant_rules(rules: &Rules, update: &[u8]) -> Rules {
-    rules.iter()
-        .filter(|(prev, posts)| update.contains(prev) && update.contains(posts))
-        .map(|(prev, posts)| (*prev, *posts))
-        .collect()
+    let mut rules = rules.clone();
+    rules.retain(|prev, post| update.contains(prev) && update.contains(post));
+    rules
 }
 
 type Page = u8;
-type Rules = MultiMap<Page, Page>;
+type Rules = BiMultiMap<Page, Page>;
 
 #[derive(Debug, PartialEq, Eq)]
 struct Input {
@@ -97,27 +90,15 @@ struct Input {
 }
 
 fn parse_input(input: &str) -> Input {
-    let mut rules = Rules::new();
-    let mut updates = Vec::new();
-
-    let mut lines = input.lines();
-
-    for line in &mut lines {
-        if line.is_empty() {
-            break;
-        }
+    let mut sections = parse::sections(input).into_iter();
 
-        let mut key_value = line.split('|');
-        let key = key_value.next().unwrap().parse::<u8>().unwrap();
-        let value = key_value.next().unwrap().parse::<u8>().unwrap();
+    let rules = sections.next().unwrap_or_default().iter()
+        .map(|line| line.key_value::<Page, Page>("|").unwrap())
+        .collect();
 
-        rules.insert(key, value);
-    }
-
-    for line in lines {
-        let update = line.split(',').map(|num_str| num_str.parse::<u8>().unwrap()).collect::<Vec<_>>();
-        updates.push(update);
-    }
+    let updates = sections.next().unwrap_or_default().iter()
+        .map(|line| line.list::<Page>(",").unwrap())
+        .collect();
 
     Input { rules, updates }
 }
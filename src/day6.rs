@@ -1,7 +1,10 @@
 #![allow(dead_code)]
 
-use std::collections::HashSet;
 use std::ops::Add;
+use crate::bitgrid::BitGrid;
+use crate::cycle;
+use crate::grid::Grid;
+use crate::render::{Color, Layer, Renderer};
 
 const DIRECTIONS_COUNT: usize = 4;
 const DIRECTIONS: [Point; 4] = [
@@ -20,40 +23,41 @@ struct GuardState {
 pub fn day(input: &str) -> u32 {
     let input = parse_input(input);
 
-    let mut visited_cells = HashSet::from([input.start]);
+    let mut visited_cells = BitGrid::new(input.size.x as usize, input.size.y as usize);
+    visited_cells.set(input.start.x as usize, input.start.y as usize);
     let mut current_position = GuardState {
         position: input.start,
         direction_index: 0,
     };
 
     while let Some(new_position) = get_next_guard_state(&current_position, &input) {
-        visited_cells.insert(new_position.position);
+        visited_cells.set(new_position.position.x as usize, new_position.position.y as usize);
         current_position = new_position;
     }
 
-    u32::try_from(visited_cells.len()).unwrap_or(0)
+    u32::try_from(visited_cells.count_ones()).unwrap_or(0)
 }
 
 pub fn day_2(input: &str) -> u32 {
     let input = parse_input(input);
 
-    let mut visited_cells = HashSet::new();
+    let mut visited_cells = BitGrid::new(input.size.x as usize, input.size.y as usize);
     let mut current_position = GuardState {
         position: input.start,
         direction_index: 0,
     };
 
     while let Some(new_position) = get_next_guard_state(&current_position, &input) {
-        visited_cells.insert(new_position.position);
+        visited_cells.set(new_position.position.x as usize, new_position.position.y as usize);
         current_position = new_position;
     }
     
     let mut loop_count = 0;
-    for &cell_to_try in visited_cells.iter() {
+    for (x, y) in visited_cells.iter() {
         let mut new_input = input.clone();
-        new_input.obstacles.insert(cell_to_try);
+        new_input.obstacles.set(x, y);
         
-        if detect_cycle(&new_input).is_some() {
+        if detect_cycle(&new_input) {
             loop_count += 1;
         }
     }
@@ -61,21 +65,44 @@ pub fn day_2(input: &str) -> u32 {
     loop_count
 }
 
-fn detect_cycle(input: &Input) -> Option<()> {
+fn detect_cycle(input: &Input) -> bool {
     let initial_position = GuardState {
         position: input.start,
         direction_index: 0,
     };
-    
-    let mut turtle = get_next_guard_state(&initial_position, input)?;
-    let mut hare = get_next_guard_state(&turtle, input)?;
 
-    while turtle != hare {
-        turtle = get_next_guard_state(&turtle, input)?;
-        hare = get_next_guard_state(&get_next_guard_state(&hare, input)?, input)?;
+    cycle::has_cycle(initial_position, |state| get_next_guard_state(state, input))
+}
+
+fn render_guard_path(input: &Input) -> String {
+    let base = Grid::from_fn(input.size.x as usize, input.size.y as usize, |x, y|
+        if input.obstacles.test(x, y) { '#' } else { '.' }
+    );
+
+    let mut visited = Layer::new("visited", 'X').with_color(Color::Yellow);
+    let mut turns = Layer::new("turn", '+').with_color(Color::Cyan);
+    let start = Layer::new("start", '^').with_color(Color::Green)
+        .with_cells([(input.start.x as usize, input.start.y as usize)]);
+
+    let mut current_position = GuardState {
+        position: input.start,
+        direction_index: 0,
+    };
+
+    while let Some(new_position) = get_next_guard_state(&current_position, input) {
+        visited.insert(new_position.position.x as usize, new_position.position.y as usize);
+        if new_position.direction_index != current_position.direction_index {
+            turns.insert(current_position.position.x as usize, current_position.position.y as usize);
+        }
+        current_position = new_position;
     }
-    
-    Some(())
+
+    Renderer::new(&base, |c| *c)
+        .layer(visited)
+        .layer(turns)
+        .layer(start)
+        .with_legend()
+        .render()
 }
 
 fn get_next_guard_state(prev_state: &GuardState, input: &Input) -> Option<GuardState> {
@@ -87,7 +114,7 @@ fn get_next_guard_state(prev_state: &GuardState, input: &Input) -> Option<GuardS
         return None;
     }
 
-    if input.obstacles.contains(&new_position) {
+    if input.obstacles.test(new_position.x as usize, new_position.y as usize) {
         let new_direction_idx = (prev_state.direction_index + 1) % DIRECTIONS_COUNT;
         let new_state = GuardState {
             position: prev_state.position,
@@ -113,7 +140,7 @@ struct Point {
 struct Input {
     size: Point,
     start: Point,
-    obstacles: HashSet<Point>,
+    obstacles: BitGrid,
 }
 
 impl Add<i32> for Point {
@@ -138,7 +165,7 @@ fn parse_input(input: &str) -> Input {
 
     let mut size = Point::default();
     let mut start = Point::default();
-    let mut obstacles = HashSet::new();
+    let mut obstacles = Vec::new();
 
     let lines = (0..).zip(input.lines());
     for (y, line) in lines {
@@ -152,17 +179,23 @@ fn parse_input(input: &str) -> Input {
                     start = Point { x, y };
                 }
                 OBSTACLE => {
-                    obstacles.insert(Point { x, y });
+                    obstacles.push(Point { x, y });
                 }
                 _ => {}
             }
         }
     }
 
+    let size = size + 1;
+    let mut obstacles_grid = BitGrid::new(size.x as usize, size.y as usize);
+    for obstacle in obstacles {
+        obstacles_grid.set(obstacle.x as usize, obstacle.y as usize);
+    }
+
     Input {
-        size: size + 1,
+        size,
         start,
-        obstacles,
+        obstacles: obstacles_grid,
     }
 }
 
@@ -193,14 +226,15 @@ mod tests {
 #..
 .#.
 "#;
+        let mut expected_obstacles = BitGrid::new(3, 4);
+        expected_obstacles.set(2, 0);
+        expected_obstacles.set(0, 2);
+        expected_obstacles.set(1, 3);
+
         let expected_input = Input {
             size: Point { x: 3, y: 4 },
             start: Point { x: 1, y: 1 },
-            obstacles: HashSet::from([
-                Point { x: 2, y: 0 },
-                Point { x: 0, y: 2 },
-                Point { x: 1, y: 3 },
-            ]),
+            obstacles: expected_obstacles,
         };
 
         let actual_input = parse_input(input);
@@ -208,6 +242,25 @@ mod tests {
         assert_eq!(expected_input, actual_input);
     }
 
+    #[test]
+    fn test_render_guard_path() {
+        let input = r#".#..
+...#
+.^..
+"#;
+
+        let expected_output = [
+            ".#..",
+            ".\x1b[36m+\x1b[0m\x1b[36m+\x1b[0m#",
+            ".\x1b[32m^\x1b[0m\x1b[33mX\x1b[0m.",
+            "\x1b[33mX\x1b[0m visited",
+            "\x1b[36m+\x1b[0m turn",
+            "\x1b[32m^\x1b[0m start",
+        ].join("\n");
+
+        assert_eq!(expected_output, render_guard_path(&parse_input(input)));
+    }
+
     #[test]
     fn test_day_2() {
         let input = r#"....#.....
@@ -1,20 +1,24 @@
 #![allow(dead_code)]
 
-pub fn day(input: &str) -> u64 {
+use crate::bigint::BigUint;
+use crate::math;
+use crate::parse;
+
+pub fn day(input: &str) -> BigUint {
     const OPERATIONS: [Operation; 2] = [Operation::Add, Operation::Mul];
     sum_equations(input, OPERATIONS)
 }
 
-pub fn day_2(input: &str) -> u64 {
+pub fn day_2(input: &str) -> BigUint {
     const OPERATIONS: [Operation; 3] = [Operation::Add, Operation::Mul, Operation::Concat];
     sum_equations(input, OPERATIONS)
 }
 
-fn sum_equations<const N: usize>(input: &str, operations_set: [Operation; N]) -> u64 {
+fn sum_equations<const N: usize>(input: &str, operations_set: [Operation; N]) -> BigUint {
     let input = parse_input(input);
     let sum = input.equations.iter().filter_map(
         |equation: &Equation| if check_equation(equation, operations_set) {
-            Some(equation.result)
+            Some(BigUint::from(equation.result))
         } else {
             None
         }
@@ -30,18 +34,28 @@ fn sum_equations<const N: usize>(input: &str, operations_set: [Operation; N]) ->
 fn check_equation<const N: usize>(equation: &Equation, operations_set: [Operation; N]) -> bool {
     let operations_iter = OperationsIter::new(equation.operands.len() - 1, operations_set);
 
+    // a partial result above the target (or overflowing) only grows from there, unless a later
+    // `* 0` resets it, so it is kept as None and the search gives up once no zero is left
+    let last_zero = equation.operands.iter().rposition(|&operand| operand == 0);
+
     for operations in operations_iter {
-        let mut operands_iter = equation.operands.iter();
-        let first_operand = operands_iter.next().unwrap();
-        let result: u64 = operands_iter.zip(operations.iter()).fold(*first_operand as u64, |acc, (operand, operation)| {
-            match operation {
-                Operation::Add => { acc + *operand as u64 }
-                Operation::Mul => { acc * *operand as u64 }
-                Operation::Concat => { format!("{}{}", acc, operand).parse().unwrap() }
+        let mut result = Some(equation.operands[0] as u64);
+        for ((position, &operand), operation) in equation.operands.iter().enumerate().skip(1).zip(operations.iter()) {
+            let operand = operand as u64;
+            result = match (result, operation) {
+                (_, Operation::Mul) if operand == 0 => Some(0),
+                (None, _) => None,
+                (Some(acc), Operation::Add) => acc.checked_add(operand),
+                (Some(acc), Operation::Mul) => acc.checked_mul(operand),
+                (Some(acc), Operation::Concat) => math::concat_digits(acc, operand),
+            }.filter(|value| *value <= equation.result);
+
+            if result.is_none() && last_zero.is_none_or(|zero| zero <= position) {
+                break;
             }
-        });
+        }
 
-        if result == equation.result {
+        if result == Some(equation.result) {
             return true;
         }
     }
@@ -123,20 +137,13 @@ struct Input {
 }
 
 fn parse_input(input: &str) -> Input {
-    let lines: Vec<_> = input.lines().filter(|line| !line.is_empty()).collect();
-
-    let equations = lines.iter().filter_map(|&line|
-        if let [result, operands] = line.split(":").collect::<Vec<_>>().as_slice() {
-            Some(Equation {
-                result: result.parse().unwrap(),
-                operands: operands.trim().split(" ").map(
-                    |operand| operand.parse().unwrap()
-                ).collect(),
-            })
-        } else {
-            None
+    let equations = parse::lines(input).map(|line| {
+        let (result, operands) = line.split_once(":").unwrap();
+        Equation {
+            result: result.parse().unwrap(),
+            operands: operands.fields().unwrap(),
         }
-    ).collect();
+    }).collect();
 
     Input {
         equations
@@ -159,7 +166,7 @@ mod tests {
 21037: 9 7 18 13
 292: 11 6 16 20"#;
 
-        assert_eq!(3749, day(input));
+        assert_eq!(BigUint::from(3749u32), day(input));
     }
 
     #[test]
@@ -220,8 +227,21 @@ mod tests {
             operands: vec![5, 3],
         };
 
+        let zero_equation = Equation {
+            result: 2,
+            operands: vec![5, 1, 0, 2],
+        };
+
+        let overflow_then_zero_equation = Equation {
+            result: 7,
+            operands: vec![u32::MAX, u32::MAX, u32::MAX, 0, 7],
+        };
+
         assert!(check_equation(&good_equation, OPERATIONS));
         assert!(!check_equation(&bad_equation, OPERATIONS));
+        assert!(check_equation(&zero_equation, OPERATIONS));
+        assert!(check_equation(&overflow_then_zero_equation, OPERATIONS));
+        assert_eq!(BigUint::from(2u32), day("2: 5 1 0 2\n"));
     }
 
     #[test]
@@ -238,8 +258,14 @@ mod tests {
             operands: vec![5, 3],
         };
 
+        let overflowing_equation = Equation {
+            result: u64::MAX,
+            operands: vec![u32::MAX, u32::MAX, u32::MAX],
+        };
+
         assert!(check_equation(&good_equation, OPERATIONS));
         assert!(!check_equation(&bad_equation, OPERATIONS));
+        assert!(!check_equation(&overflowing_equation, OPERATIONS));
     }
 
     #[test]
@@ -254,6 +280,6 @@ mod tests {
 21037: 9 7 18 13
 292: 11 6 16 20"#;
 
-        assert_eq!(11387, day_2(input));
+        assert_eq!(BigUint::from(11387u32), day_2(input));
     }
 }
@@ -1,16 +1,17 @@
 #![allow(dead_code)]
 
 use std::borrow::Borrow;
-use std::collections::HashSet;
 use std::hash::Hash;
-use std::ops::{Add, AddAssign, Mul, Sub};
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
 use itertools::Itertools;
-use crate::multimap::MultiMap;
+use crate::bitgrid::BitGrid;
+use crate::math;
+use crate::ordered_multimap::OrderedMultiMap;
 
 pub fn day(input: &str) -> u32 {
     let input = parse_input(input);
 
-    let mut antinodes = HashSet::new();
+    let mut antinodes = BitGrid::new(input.size.x as usize, input.size.y as usize);
 
     for group in input.antennas_groups {
         for (a1, a2) in group.iter().tuple_combinations() {
@@ -18,31 +19,32 @@ pub fn day(input: &str) -> u32 {
             let x2 = a2 * 2 - a1;
 
             if is_point_in(&x1, &input.size) {
-                antinodes.insert(x1);
+                antinodes.set(x1.x as usize, x1.y as usize);
             }
 
             if is_point_in(&x2, &input.size) {
-                antinodes.insert(x2);
+                antinodes.set(x2.x as usize, x2.y as usize);
             }
         }
     }
 
-    antinodes.len() as u32
+    antinodes.count_ones() as u32
 }
 
 pub fn day_2(input: &str) -> u32 {
     let input = parse_input(input);
 
-    let mut antinodes = HashSet::new();
+    let mut antinodes = BitGrid::new(input.size.x as usize, input.size.y as usize);
 
     for group in input.antennas_groups {
         for (a1, a2) in group.iter().tuple_combinations() {
-            add_points(a1, a1 - a2, &input.size, &mut antinodes);
-            add_points(a2, a2 - a1, &input.size, &mut antinodes);
+            let step = reduce_step(a2 - a1);
+            add_points(a1, step, &input.size, &mut antinodes);
+            add_points(a1, step * -1, &input.size, &mut antinodes);
         }
     }
 
-    antinodes.len() as u32
+    antinodes.count_ones() as u32
 }
 
 fn is_point_in(point: &Point, size: &Point) -> bool {
@@ -51,10 +53,16 @@ fn is_point_in(point: &Point, size: &Point) -> bool {
         && point.y < size.y
 }
 
-fn add_points<T: Borrow<Point>>(initial_point: &Point, step: T, size: &Point, set_to_add: &mut HashSet<Point>) {
+// the smallest step between grid points on the line through the original step
+fn reduce_step(step: Point) -> Point {
+    let divisor = math::gcd(step.x.unsigned_abs() as u64, step.y.unsigned_abs() as u64);
+    step / divisor as i32
+}
+
+fn add_points<T: Borrow<Point>>(initial_point: &Point, step: T, size: &Point, set_to_add: &mut BitGrid) {
     let mut current_point = *initial_point;
     while is_point_in(&current_point, size) {
-        set_to_add.insert(current_point);
+        set_to_add.set(current_point.x as usize, current_point.y as usize);
         current_point += step.borrow();
     }
 }
@@ -112,6 +120,14 @@ impl Mul<i32> for &Point {
     }
 }
 
+impl Div<i32> for Point {
+    type Output = Self;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        Self { x: self.x / rhs, y: self.y / rhs }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct Input {
     size: Point,
@@ -121,7 +137,7 @@ struct Input {
 fn parse_input(input: &str) -> Input {
     const EMPTY_CELL: char = '.';
     let mut size = Point::default();
-    let mut antennas_groups = MultiMap::new();
+    let mut antennas_groups = OrderedMultiMap::new();
 
     let lines = (0..).zip(input.lines());
     for (y, line) in lines {
@@ -196,6 +212,13 @@ mod tests {
         assert_eq!(actual_input, expected_input);
     }
 
+    #[test]
+    fn test_reduce_step() {
+        assert_eq!(Point { x: 2, y: -1 }, reduce_step(Point { x: 6, y: -3 }));
+        assert_eq!(Point { x: 0, y: 1 }, reduce_step(Point { x: 0, y: 4 }));
+        assert_eq!(Point { x: 1, y: 3 }, reduce_step(Point { x: 1, y: 3 }));
+    }
+
     #[test]
     fn test_day_2() {
         let input = r#"............
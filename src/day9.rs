@@ -4,14 +4,18 @@ use std::fmt::{Debug, Display, Formatter};
 use std::ops::{Deref, DerefMut};
 use itertools::Itertools;
 use BlockType::*;
+use crate::bigint::BigUint;
+use crate::grid::Grid;
+use crate::image::{Image, Rgb, BLACK};
+use crate::interval::{IntervalSet, RangeMap};
 
-pub fn day(input: &str) -> u64 {
+pub fn day(input: &str) -> BigUint {
     let mut blocks = parse_input(input).blocks;
     compress(&mut blocks);
     count_checksum(&blocks)
 }
 
-pub fn day_2(input: &str) -> u64 {
+pub fn day_2(input: &str) -> BigUint {
     let mut blocks = parse_input(input).blocks;
     defragmentate(&mut blocks);
     count_checksum(&blocks)
@@ -49,42 +53,22 @@ struct File {
 
 fn defragmentate(blocks: &mut Blocks) {
     let files = get_file_list(blocks);
+    let mut free_space = get_free_space(blocks);
 
     for file in files.iter().rev() {
-        if let Some(free_space_start) = find_free_space(blocks, file.size, file.start) {
+        if let Some(free_space_start) = free_space.first_fit(file.size, file.start) {
             move_blocks(blocks, file.start, free_space_start, file.size);
+            free_space.remove(free_space_start..free_space_start + file.size);
+            free_space.insert(file.start..file.start + file.size);
         }
     }
 }
 
-fn find_free_space(blocks: &Blocks, needed_size: usize, last_block: usize) -> Option<usize> {
-    let mut start = None;
-    let mut size = 0;
-
-    for (i, block) in blocks.iter().enumerate().take(last_block) {
-        match block {
-            Empty if start.is_some() => {
-                size += 1;
-            }
-            Empty => {
-                start = Some(i);
-                size = 1;
-            }
-            File(_) => {
-                start = None;
-            }
-        }
-
-        if size == needed_size {
-            break;
-        }
-    }
-
-    if size != needed_size {
-        return None;
-    }
-
-    start
+fn get_free_space(blocks: &Blocks) -> IntervalSet<usize> {
+    blocks.iter().enumerate()
+        .filter(|(_, block)| **block == Empty)
+        .map(|(i, _)| i..i + 1)
+        .collect()
 }
 
 fn move_blocks(blocks: &mut Blocks, src: usize, dest: usize, size: usize) {
@@ -95,56 +79,46 @@ fn move_blocks(blocks: &mut Blocks, src: usize, dest: usize, size: usize) {
 }
 
 fn get_file_list(blocks: &Blocks) -> Vec<File> {
-    let mut files = Vec::new();
-
-    let mut current_file: Option<File> = None;
-    for (i, block) in blocks.iter().enumerate() {
-        current_file = match (block, current_file) {
-            (File(id), Some(file)) if file.id != *id => {
-                files.push(file);
-                Some(File {
-                    id: *id,
-                    start: i,
-                    size: 1,
-                })
-            }
-            (File(_), Some(mut file)) => {
-                file.size += 1;
-                Some(file)
-            }
-            (File(id), None) => {
-                Some(File {
-                    id: *id,
-                    start: i,
-                    size: 1,
-                })
-            }
-            (Empty, Some(file)) => {
-                files.push(file);
-                None
-            }
-            _ => { None }
-        }
-    }
-
-    if let Some(file) = current_file {
-        files.push(file);
-    }
-
-    files
+    let extents: RangeMap<usize, BlockType> = blocks.iter().enumerate()
+        .filter(|(_, block)| **block != Empty)
+        .map(|(i, block)| (i..i + 1, *block))
+        .collect();
+
+    extents.iter().filter_map(|(range, block)| match block {
+        File(id) => Some(File { id: *id, start: range.start, size: range.len() }),
+        Empty => None,
+    }).collect()
 }
 
-fn count_checksum(blocks: &Blocks) -> u64 {
-    blocks.iter().enumerate().fold(0, |acc, (idx, block)| {
+fn count_checksum(blocks: &Blocks) -> BigUint {
+    blocks.iter().enumerate().filter_map(|(idx, block)| {
         if let File(id) = block {
-            acc + idx as u64 * *id as u64
+            Some(BigUint::from(idx as u64) * BigUint::from(*id))
         } else {
-            acc
+            None
         }
+    }).sum()
+}
+
+// disk layout as a one cell high strip, empty blocks are black
+fn disk_image(blocks: &Blocks, scale: usize) -> Image {
+    let strip = Grid::try_new(blocks.len(), 1, blocks.to_vec()).unwrap();
+    Image::from_grid(&strip, scale, |block| match block {
+        Empty => BLACK,
+        File(id) => file_color(*id),
     })
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+fn file_color(id: u32) -> Rgb {
+    let id = id as u64;
+    [
+        (64 + id * 67 % 192) as u8,
+        (64 + id * 131 % 192) as u8,
+        (64 + id * 197 % 192) as u8,
+    ]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum BlockType {
     Empty,
     File(u32),
@@ -234,21 +208,21 @@ mod tests {
     fn test_day() {
         let input = r#"2333133121414131402"#;
 
-        assert_eq!(1928, day(input));
+        assert_eq!(BigUint::from(1928u32), day(input));
     }
 
     #[test]
     fn test_day_2() {
         let input = r#"2333133121414131402"#;
 
-        assert_eq!(2858, day_2(input));
+        assert_eq!(BigUint::from(2858u32), day_2(input));
     }
 
     #[test]
     fn test_day_2_2() {
         let input = r#"1313165"#;
 
-        assert_eq!(169, day_2(input));
+        assert_eq!(BigUint::from(169u32), day_2(input));
     }
 
     #[test]
@@ -361,13 +335,15 @@ mod tests {
     }
 
     #[test]
-    fn test_find_free_space() {
+    fn test_get_free_space() {
         let blocks = "00...111..2....333.44".into();
 
-        let space1 = find_free_space(&blocks, 3, 14).unwrap();
-        let space2 = find_free_space(&blocks, 2, 4).unwrap();
-        let space3 = find_free_space(&blocks, 4, 100).unwrap();
-        let space4 = find_free_space(&blocks, 5, 100);
+        let free_space = get_free_space(&blocks);
+
+        let space1 = free_space.first_fit(3, 14).unwrap();
+        let space2 = free_space.first_fit(2, 4).unwrap();
+        let space3 = free_space.first_fit(4, 100).unwrap();
+        let space4 = free_space.first_fit(5, 100);
 
         assert_eq!(space1, 2);
         assert_eq!(space2, 2);
@@ -375,6 +351,19 @@ mod tests {
         assert_eq!(space4, None);
     }
 
+    #[test]
+    fn test_disk_image() {
+        let blocks = "0..1".into();
+
+        let image = disk_image(&blocks, 3);
+
+        assert_eq!((12, 3), (image.width(), image.height()));
+        assert_eq!(file_color(0), image.pixel(2, 2));
+        assert_eq!(BLACK, image.pixel(3, 0));
+        assert_eq!(file_color(1), image.pixel(11, 1));
+        assert_eq!([253, 253, 187], file_color(u32::MAX));
+    }
+
     #[test]
     fn test_move_file() {
         let mut blocks = "00...111..2....333.44".into();
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// union-find over dense indices 0..len
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    set_count: usize,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            set_count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    // adds a new singleton set and returns its index
+    pub fn make_set(&mut self) -> usize {
+        let index = self.parents.len();
+        self.parents.push(index);
+        self.ranks.push(0);
+        self.sizes.push(1);
+        self.set_count += 1;
+        index
+    }
+
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = element;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    // returns false if both elements were already in the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.ranks[a] < self.ranks[b] {
+            (a, b) = (b, a);
+        }
+        if self.ranks[a] == self.ranks[b] {
+            self.ranks[a] += 1;
+        }
+
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.set_count -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    // every set with its elements in increasing order, sets ordered by their smallest element
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut component_of_root = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+
+        for element in 0..self.len() {
+            let root = self.find(element);
+            let index = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[index].push(element);
+        }
+
+        components
+    }
+}
+
+// union-find over arbitrary hashable keys, unknown keys become singleton sets on first use
+#[derive(Debug, Clone)]
+pub struct KeyedDisjointSet<K> {
+    indices: HashMap<K, usize>,
+    keys: Vec<K>,
+    set: DisjointSet,
+}
+
+impl<K: Eq + Hash + Clone> KeyedDisjointSet<K> {
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            keys: Vec::new(),
+            set: DisjointSet::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn set_count(&self) -> usize {
+        self.set.set_count()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    // returns the dense index of the key
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(index) = self.indices.get(&key) {
+            return *index;
+        }
+
+        let index = self.set.make_set();
+        self.indices.insert(key.clone(), index);
+        self.keys.push(key);
+        index
+    }
+
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = *self.indices.get(key)?;
+        let root = self.set.find(index);
+        Some(&self.keys[root])
+    }
+
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.insert(a);
+        let b = self.insert(b);
+        self.set.union(a, b)
+    }
+
+    pub fn same_set(&mut self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(a), Some(b)) => self.set.same_set(*a, *b),
+            _ => false,
+        }
+    }
+
+    pub fn set_size(&mut self, key: &K) -> Option<usize> {
+        let index = *self.indices.get(key)?;
+        Some(self.set.set_size(index))
+    }
+
+    // sets in insertion order of their first key, keys in insertion order
+    pub fn components(&mut self) -> Vec<Vec<&K>> {
+        self.set.components().into_iter()
+            .map(|component| component.into_iter().map(|index| &self.keys[index]).collect())
+            .collect()
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for KeyedDisjointSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut set = DisjointSet::new(6);
+
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+
+        assert!(set.same_set(0, 3));
+        assert!(!set.same_set(0, 4));
+        assert_eq!(4, set.set_size(2));
+        assert_eq!(1, set.set_size(5));
+        assert_eq!(3, set.set_count());
+    }
+
+    #[test]
+    fn test_components() {
+        let mut set = DisjointSet::new(5);
+        set.union(4, 1);
+        set.union(3, 0);
+
+        assert_eq!(vec![vec![0, 3], vec![1, 4], vec![2]], set.components());
+
+        let new = set.make_set();
+        assert_eq!(5, new);
+        assert_eq!(4, set.set_count());
+    }
+
+    #[test]
+    fn test_keyed() {
+        let mut set = KeyedDisjointSet::new();
+
+        set.union((0, 0), (0, 1));
+        set.union((0, 1), (1, 1));
+        set.insert((5, 5));
+
+        assert!(set.same_set(&(0, 0), &(1, 1)));
+        assert!(!set.same_set(&(0, 0), &(5, 5)));
+        assert!(!set.same_set(&(0, 0), &(9, 9)));
+        assert_eq!(Some(3), set.set_size(&(1, 1)));
+        assert_eq!(None, set.set_size(&(9, 9)));
+        assert_eq!(2, set.set_count());
+        assert_eq!(
+            vec![vec![&(0, 0), &(0, 1), &(1, 1)], vec![&(5, 5)]],
+            set.components()
+        );
+    }
+
+    #[test]
+    fn test_keyed_find() {
+        let mut set = KeyedDisjointSet::new();
+
+        set.union("a", "b");
+        set.union("c", "b");
+
+        let root = *set.find(&"c").unwrap();
+        assert_eq!(Some(&root), set.find(&"a"));
+        assert_eq!(None, set.find(&"z"));
+    }
+}
@@ -0,0 +1,227 @@
+use std::borrow::Borrow;
+use std::slice;
+
+// immutable MultiMap in compressed-sparse-row layout: sorted keys, and the sorted values of
+// `keys[i]` at `values[offsets[i]..offsets[i + 1]]`; lookups are binary searches
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FrozenMultiMap<K, V> {
+    keys: Vec<K>,
+    offsets: Vec<usize>,
+    values: Vec<V>,
+}
+
+impl<K: Ord, V: Ord> FrozenMultiMap<K, V> {
+    // groups with an empty value list are dropped, duplicate values are removed
+    pub fn from_groups(mut groups: Vec<(K, Vec<V>)>) -> Self {
+        groups.retain(|(_, values)| !values.is_empty());
+        groups.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut merged: Vec<(K, Vec<V>)> = Vec::with_capacity(groups.len());
+        for (key, group) in groups {
+            match merged.last_mut() {
+                Some((last_key, values)) if *last_key == key => values.extend(group),
+                _ => merged.push((key, group)),
+            }
+        }
+
+        let mut keys = Vec::with_capacity(merged.len());
+        let mut offsets = vec![0];
+        let mut values = Vec::new();
+
+        for (key, mut group) in merged {
+            group.sort_unstable();
+            group.dedup();
+            keys.push(key);
+            values.extend(group);
+            offsets.push(values.len());
+        }
+
+        Self { keys, offsets, values }
+    }
+
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<slice::Iter<'_, V>>
+    where
+        K: Borrow<Q>,
+    {
+        self.group(key).map(|values| values.iter())
+    }
+
+    pub fn contains<Q, R>(&self, key: &Q, value: &R) -> bool
+    where
+        K: Borrow<Q>,
+        V: Borrow<R>,
+        Q: ?Sized + Ord,
+        R: ?Sized + Ord,
+    {
+        self.group(key).is_some_and(|values| values.binary_search_by(|probe| probe.borrow().cmp(value)).is_ok())
+    }
+
+    // number of (key, value) pairs
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn key_len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> slice::Iter<'_, K> {
+        self.keys.iter()
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            map: self,
+            key_index: 0,
+            value_index: 0,
+        }
+    }
+
+    // all values, grouped by key in key order
+    pub fn values(&self) -> slice::Iter<'_, V> {
+        self.values.iter()
+    }
+
+    pub fn grouped_values(&self) -> GroupedValues<'_, K, V> {
+        GroupedValues {
+            map: self,
+            key_index: 0,
+        }
+    }
+
+    fn group<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&[V]>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.keys.binary_search_by(|probe| probe.borrow().cmp(key)).ok()?;
+        Some(self.group_at(index))
+    }
+}
+
+impl<K, V> FrozenMultiMap<K, V> {
+    fn group_at(&self, index: usize) -> &[V] {
+        &self.values[self.offsets[index]..self.offsets[index + 1]]
+    }
+}
+
+impl<K: Ord, V: Ord> FromIterator<(K, V)> for FrozenMultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        Self::from_groups(iter.into_iter().map(|(key, value)| (key, vec![value])).collect())
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    map: &'a FrozenMultiMap<K, V>,
+    key_index: usize,
+    value_index: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.map.values.get(self.value_index)?;
+        while self.map.offsets[self.key_index + 1] <= self.value_index {
+            self.key_index += 1;
+        }
+
+        self.value_index += 1;
+        Some((&self.map.keys[self.key_index], value))
+    }
+}
+
+pub struct GroupedValues<'a, K, V> {
+    map: &'a FrozenMultiMap<K, V>,
+    key_index: usize,
+}
+
+impl<'a, K, V> Iterator for GroupedValues<'a, K, V> {
+    type Item = (&'a K, slice::Iter<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.map.keys.get(self.key_index)?;
+        let values = self.map.group_at(self.key_index);
+        self.key_index += 1;
+        Some((key, values.iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+    use std::time::Instant;
+    use crate::multimap::MultiMap;
+    use super::*;
+
+    #[test]
+    fn test_freeze() {
+        let frozen = MultiMap::from([
+            (33, 55),
+            (11, 33),
+            (11, 22),
+            (22, 44),
+        ]).freeze();
+
+        assert_eq!(
+            vec![(&11, &22), (&11, &33), (&22, &44), (&33, &55)],
+            frozen.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![&22, &33], frozen.get(&11).unwrap().collect::<Vec<_>>());
+        assert!(frozen.get(&44).is_none());
+        assert!(frozen.contains(&22, &44));
+        assert!(!frozen.contains(&22, &55));
+        assert_eq!((4, 3), (frozen.len(), frozen.key_len()));
+    }
+
+    #[test]
+    fn test_grouped_values() {
+        let frozen = FrozenMultiMap::from_iter([(2, 'b'), (1, 'a'), (2, 'a'), (2, 'b')]);
+
+        let groups = frozen.grouped_values()
+            .map(|(key, values)| (*key, values.copied().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(1, vec!['a']), (2, vec!['a', 'b'])], groups);
+        assert_eq!(vec![&'a', &'a', &'b'], frozen.values().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_borrowed_lookups() {
+        let frozen = MultiMap::from([("a".to_string(), "x".to_string())]).freeze();
+
+        assert!(frozen.contains("a", "x"));
+        assert_eq!(1, frozen.get("a").unwrap().len());
+    }
+
+    // compares against the hash version; cargo test --release frozen -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_lookups() {
+        let pairs = (0..100_000u32).map(|i| (i % 1000, i.wrapping_mul(2_654_435_761) % 5000)).collect::<Vec<_>>();
+        let hashed = pairs.iter().copied().collect::<MultiMap<_, _>>();
+        let frozen = hashed.clone().freeze();
+
+        let started = Instant::now();
+        let hits = (0..1_000_000u32).filter(|i| hashed.contains(&(i % 1000), &(i % 5000))).count();
+        println!("hash contains:   {:?} ({hits} hits)", started.elapsed());
+
+        let started = Instant::now();
+        let frozen_hits = (0..1_000_000u32).filter(|i| frozen.contains(&(i % 1000), &(i % 5000))).count();
+        println!("frozen contains: {:?} ({frozen_hits} hits)", started.elapsed());
+
+        let started = Instant::now();
+        let sum = (0..100).map(|_| hashed.values().map(|value| *value as u64).sum::<u64>()).sum::<u64>();
+        println!("hash iteration:   {:?}", started.elapsed());
+
+        let started = Instant::now();
+        let frozen_sum = (0..100).map(|_| frozen.values().map(|value| *value as u64).sum::<u64>()).sum::<u64>();
+        println!("frozen iteration: {:?}", started.elapsed());
+
+        assert_eq!(hits, frozen_hits);
+        assert_eq!(black_box(sum), black_box(frozen_sum));
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use crate::multimap::MultiMap;
+
+// a cycle found while sorting, the first node repeats at the end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    pub cycle: Vec<N>,
+}
+
+// read-only view of a MultiMap<N, N> as directed adjacency, keys point to their values
+pub struct Graph<'a, N: Eq + Hash, S = RandomState> {
+    edges: &'a MultiMap<N, N, S>,
+}
+
+impl<'a, N: Eq + Hash + Clone, S: BuildHasher + Clone> Graph<'a, N, S> {
+    pub fn new(edges: &'a MultiMap<N, N, S>) -> Self {
+        Self { edges }
+    }
+
+    // every node that appears on either end of an edge
+    pub fn nodes(&self) -> Vec<N> {
+        let mut seen = HashSet::new();
+        self.edges.iter()
+            .flat_map(|(from, to)| [from, to])
+            .filter(|node| seen.insert(*node))
+            .cloned()
+            .collect()
+    }
+
+    pub fn successors(&self, node: &N) -> impl Iterator<Item=&'a N> {
+        self.edges.get_or_empty(node)
+    }
+
+    // nodes reachable from `start`, `start` included
+    pub fn reachable(&self, start: &N) -> HashSet<N> {
+        self.bfs_order(start).into_iter().collect()
+    }
+
+    pub fn bfs_order(&self, start: &N) -> Vec<N> {
+        let mut visited = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([start.clone()]);
+        let mut order = Vec::new();
+
+        while let Some(node) = queue.pop_front() {
+            for next in self.successors(&node) {
+                if visited.insert(next.clone()) {
+                    queue.push_back(next.clone());
+                }
+            }
+            order.push(node);
+        }
+
+        order
+    }
+
+    // preorder
+    pub fn dfs_order(&self, start: &N) -> Vec<N> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start.clone()];
+        let mut order = Vec::new();
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.clone()) {
+                continue;
+            }
+            stack.extend(self.successors(&node).filter(|next| !visited.contains(*next)).cloned());
+            order.push(node);
+        }
+
+        order
+    }
+
+    // every edge goes from an earlier node to a later one
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut finished = HashSet::new();
+        let mut order = Vec::new();
+
+        for root in self.nodes() {
+            if finished.contains(&root) {
+                continue;
+            }
+
+            // frames of the current path, and the position of every node on it
+            let mut on_path = HashMap::from([(root.clone(), 0)]);
+            let mut stack = vec![(root.clone(), self.successors(&root))];
+
+            while let Some((_, successors)) = stack.last_mut() {
+                match successors.next() {
+                    Some(next) if on_path.contains_key(next) => {
+                        let mut cycle = stack[on_path[next]..].iter()
+                            .map(|(node, _)| node.clone())
+                            .collect::<Vec<_>>();
+                        cycle.push(next.clone());
+                        return Err(CycleError { cycle });
+                    }
+                    Some(next) if finished.contains(next) => {}
+                    Some(next) => {
+                        on_path.insert(next.clone(), stack.len());
+                        stack.push((next.clone(), self.successors(next)));
+                    }
+                    None => {
+                        let (node, _) = stack.pop().unwrap();
+                        on_path.remove(&node);
+                        finished.insert(node.clone());
+                        order.push(node);
+                    }
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    // Tarjan; components come out in reverse topological order of the condensed graph
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        let mut tarjan = Tarjan {
+            indices: HashMap::new(),
+            low_links: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+
+        for root in self.nodes() {
+            if tarjan.indices.contains_key(&root) {
+                continue;
+            }
+
+            tarjan.enter(root.clone());
+            let mut frames = vec![(root.clone(), self.successors(&root))];
+
+            while let Some((_, successors)) = frames.last_mut() {
+                match successors.next() {
+                    Some(next) if !tarjan.indices.contains_key(next) => {
+                        tarjan.enter(next.clone());
+                        frames.push((next.clone(), self.successors(next)));
+                    }
+                    Some(next) => {
+                        if tarjan.on_stack.contains(next) {
+                            let index = tarjan.indices[next];
+                            tarjan.lower(&frames.last().unwrap().0, index);
+                        }
+                    }
+                    None => {
+                        let (node, _) = frames.pop().unwrap();
+                        if let Some((parent, _)) = frames.last() {
+                            tarjan.lower(parent, tarjan.low_links[&node]);
+                        }
+                        tarjan.finish(node);
+                    }
+                }
+            }
+        }
+
+        tarjan.components
+    }
+
+    // an edge to every node reachable in one or more steps
+    pub fn transitive_closure(&self) -> MultiMap<N, N, S> {
+        let mut closure = MultiMap::with_hasher(self.edges.hasher().clone());
+
+        for node in self.edges.keys() {
+            let mut visited = HashSet::new();
+            let mut stack = self.successors(node).collect::<Vec<_>>();
+            while let Some(next) = stack.pop() {
+                if visited.insert(next) {
+                    closure.insert(node.clone(), next.clone());
+                    stack.extend(self.successors(next));
+                }
+            }
+        }
+
+        closure
+    }
+
+    // fewest edges with the same reachability; only well defined for acyclic graphs
+    pub fn transitive_reduction(&self) -> MultiMap<N, N, S> {
+        let closure = self.transitive_closure();
+        let mut reduction = self.edges.clone();
+
+        for (from, to) in self.edges.iter() {
+            let implied = self.successors(from)
+                .any(|middle| middle != to && closure.contains(middle, to));
+            if implied {
+                reduction.remove(from, to);
+            }
+        }
+
+        reduction
+    }
+}
+
+struct Tarjan<N> {
+    indices: HashMap<N, usize>,
+    low_links: HashMap<N, usize>,
+    stack: Vec<N>,
+    on_stack: HashSet<N>,
+    components: Vec<Vec<N>>,
+}
+
+impl<N: Eq + Hash + Clone> Tarjan<N> {
+    fn enter(&mut self, node: N) {
+        let index = self.indices.len();
+        self.indices.insert(node.clone(), index);
+        self.low_links.insert(node.clone(), index);
+        self.stack.push(node.clone());
+        self.on_stack.insert(node);
+    }
+
+    fn lower(&mut self, node: &N, low_link: usize) {
+        let current = self.low_links.get_mut(node).unwrap();
+        *current = (*current).min(low_link);
+    }
+
+    // called once every successor of the node is done
+    fn finish(&mut self, node: N) {
+        if self.low_links[&node] != self.indices[&node] {
+            return;
+        }
+
+        let mut component = Vec::new();
+        while let Some(member) = self.stack.pop() {
+            self.on_stack.remove(&member);
+            let is_root = member == node;
+            component.push(member);
+            if is_root {
+                break;
+            }
+        }
+        self.components.push(component);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<T: Ord>(mut items: Vec<T>) -> Vec<T> {
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn test_orders() {
+        let edges = MultiMap::from([(1, 2), (1, 3), (2, 4), (3, 4), (5, 1)]);
+        let graph = Graph::new(&edges);
+
+        let bfs = graph.bfs_order(&1);
+        let dfs = graph.dfs_order(&1);
+
+        assert_eq!(1, bfs[0]);
+        assert_eq!(4, bfs[3]);
+        assert_eq!(1, dfs[0]);
+        assert_eq!(vec![1, 2, 3, 4], sorted(dfs));
+        assert_eq!(HashSet::from([1, 2, 3, 4]), graph.reachable(&1));
+        assert_eq!(HashSet::from([4]), graph.reachable(&4));
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let edges = MultiMap::from([(97, 75), (75, 47), (97, 47), (47, 61), (75, 61), (61, 53)]);
+
+        let order = Graph::new(&edges).topological_sort().unwrap();
+
+        assert_eq!(vec![97, 75, 47, 61, 53], order);
+    }
+
+    #[test]
+    fn test_topological_sort_cycle() {
+        let edges = MultiMap::from([(1, 2), (2, 3), (3, 1), (0, 1)]);
+
+        let cycle = Graph::new(&edges).topological_sort().unwrap_err().cycle;
+
+        assert_eq!(4, cycle.len());
+        assert_eq!(cycle.first(), cycle.last());
+        assert_eq!(vec![1, 2, 3], sorted(cycle[..3].to_vec()));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let edges = MultiMap::from([(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 6)]);
+
+        let components = Graph::new(&edges).strongly_connected_components()
+            .into_iter()
+            .map(sorted)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![vec![1, 2, 3], vec![4, 5], vec![6]], sorted(components));
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let edges = (0..20_000).map(|node| (node, node + 1)).collect::<MultiMap<_, _>>();
+        let graph = Graph::new(&edges);
+
+        assert_eq!((0..=20_000).collect::<Vec<_>>(), graph.topological_sort().unwrap());
+        assert_eq!(20_001, graph.strongly_connected_components().len());
+
+        let mut cyclic = edges.clone();
+        cyclic.insert(20_000, 0);
+        let cycle = Graph::new(&cyclic).topological_sort().unwrap_err().cycle;
+        assert_eq!(20_002, cycle.len());
+        let components = Graph::new(&cyclic).strongly_connected_components();
+        assert_eq!(1, components.len());
+        assert_eq!((0..=20_000).collect::<Vec<_>>(), sorted(components[0].clone()));
+    }
+
+    #[test]
+    fn test_transitive_closure_reduction() {
+        let edges = MultiMap::from([(1, 2), (2, 3), (1, 3), (3, 4)]);
+        let graph = Graph::new(&edges);
+
+        assert_eq!(
+            MultiMap::from([(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)]),
+            graph.transitive_closure()
+        );
+        assert_eq!(MultiMap::from([(1, 2), (2, 3), (3, 4)]), graph.transitive_reduction());
+    }
+}
@@ -0,0 +1,469 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Addressing {
+    // out-of-bounds points are off the map
+    #[default]
+    Bounded,
+    // points wrap modulo width/height, as on a torus
+    Wrapping,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Hash, Eq, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point { x: self.x + rhs.x, y: self.y + rhs.y }
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Point { x: self.x * rhs, y: self.y * rhs }
+    }
+}
+
+pub trait GridView {
+    type Cell;
+
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn cell(&self, x: usize, y: usize) -> &Self::Cell;
+
+    fn rows(&self) -> Lines<'_, Self> {
+        Lines::new(self, LineKind::Row)
+    }
+
+    fn columns(&self) -> Lines<'_, Self> {
+        Lines::new(self, LineKind::Column)
+    }
+
+    // top-left to bottom-right, starting from the bottom-left corner
+    fn diagonals(&self) -> Lines<'_, Self> {
+        Lines::new(self, LineKind::Diagonal)
+    }
+
+    // top-right to bottom-left, starting from the top-left corner
+    fn anti_diagonals(&self) -> Lines<'_, Self> {
+        Lines::new(self, LineKind::AntiDiagonal)
+    }
+
+    fn transposed(&self) -> Grid<Self::Cell> where Self::Cell: Clone {
+        Grid::from_fn(self.height(), self.width(), |x, y| self.cell(y, x).clone())
+    }
+
+    fn flipped_horizontally(&self) -> Grid<Self::Cell> where Self::Cell: Clone {
+        let width = self.width();
+        Grid::from_fn(width, self.height(), |x, y| self.cell(width - 1 - x, y).clone())
+    }
+
+    fn flipped_vertically(&self) -> Grid<Self::Cell> where Self::Cell: Clone {
+        let height = self.height();
+        Grid::from_fn(self.width(), height, |x, y| self.cell(x, height - 1 - y).clone())
+    }
+
+    // clockwise
+    fn rotated(&self, rotation: Rotation) -> Grid<Self::Cell> where Self::Cell: Clone {
+        let (width, height) = (self.width(), self.height());
+        match rotation {
+            Rotation::Deg90 => Grid::from_fn(height, width, |x, y| self.cell(y, height - 1 - x).clone()),
+            Rotation::Deg180 => Grid::from_fn(width, height, |x, y| self.cell(width - 1 - x, height - 1 - y).clone()),
+            Rotation::Deg270 => Grid::from_fn(height, width, |x, y| self.cell(width - 1 - y, x).clone()),
+        }
+    }
+
+    fn sub_grid(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Grid<Self::Cell>> where Self::Cell: Clone {
+        if x + width > self.width() || y + height > self.height() {
+            return None;
+        }
+
+        Some(Grid::from_fn(width, height, |dx, dy| self.cell(x + dx, y + dy).clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+    addressing: Addressing,
+}
+
+impl<T> Grid<T> {
+    pub fn try_new(width: usize, height: usize, data: Vec<T>) -> Option<Self> {
+        if data.len() != width * height {
+            return None;
+        }
+
+        Some(Grid { width, height, data, addressing: Addressing::default() })
+    }
+
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(f(x, y));
+            }
+        }
+
+        Grid { width, height, data, addressing: Addressing::default() }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(&self.data[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(&mut self.data[y * self.width + x])
+    }
+
+    pub fn with_addressing(mut self, addressing: Addressing) -> Self {
+        self.addressing = addressing;
+        self
+    }
+
+    pub fn addressing(&self) -> Addressing {
+        self.addressing
+    }
+
+    // maps a point to the cell it addresses, if any
+    pub fn resolve(&self, point: Point) -> Option<Point> {
+        match self.addressing {
+            Addressing::Bounded if self.is_fit(point) => Some(point),
+            Addressing::Bounded => None,
+            Addressing::Wrapping if self.width == 0 || self.height == 0 => None,
+            Addressing::Wrapping => Some(Point {
+                x: point.x.rem_euclid(self.width as i32),
+                y: point.y.rem_euclid(self.height as i32),
+            }),
+        }
+    }
+
+    pub fn is_fit(&self, point: Point) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.width as i32 && point.y < self.height as i32
+    }
+
+    pub fn at(&self, point: Point) -> Option<&T> {
+        let point = self.resolve(point)?;
+        self.get(point.x as usize, point.y as usize)
+    }
+
+    pub fn at_mut(&mut self, point: Point) -> Option<&mut T> {
+        let point = self.resolve(point)?;
+        self.get_mut(point.x as usize, point.y as usize)
+    }
+
+    pub fn step(&self, point: Point, direction: Point) -> Option<Point> {
+        self.resolve(point + direction)
+    }
+
+    // yields the points after each step from `start`; never ends on a wrapping grid
+    pub fn ray(&self, start: Point, direction: Point) -> Ray<'_, T> {
+        Ray { grid: self, current: self.resolve(start), direction }
+    }
+}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    current: Option<Point>,
+    direction: Point,
+}
+
+impl<T> Iterator for Ray<'_, T> {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.grid.step(self.current?, self.direction);
+        self.current = next;
+        next
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid { width, height, data: vec![value; width * height], addressing: Addressing::default() }
+    }
+}
+
+impl Grid<char> {
+    pub fn try_from_lines(lines: &[&str]) -> Option<Self> {
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut data = Vec::with_capacity(width * lines.len());
+
+        for line in lines {
+            let len_before = data.len();
+            data.extend(line.chars());
+            if data.len() - len_before != width {
+                return None;
+            }
+        }
+
+        Some(Grid { width, height: lines.len(), data, addressing: Addressing::default() })
+    }
+}
+
+impl<T> GridView for Grid<T> {
+    type Cell = T;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &T {
+        &self.data[y * self.width + x]
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Row,
+    Column,
+    Diagonal,
+    AntiDiagonal,
+}
+
+pub struct Lines<'a, G: ?Sized> {
+    grid: &'a G,
+    kind: LineKind,
+    index: usize,
+    count: usize,
+}
+
+impl<'a, G: GridView + ?Sized> Lines<'a, G> {
+    fn new(grid: &'a G, kind: LineKind) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let count = match kind {
+            LineKind::Row => height,
+            LineKind::Column => width,
+            LineKind::Diagonal | LineKind::AntiDiagonal if width == 0 || height == 0 => 0,
+            LineKind::Diagonal | LineKind::AntiDiagonal => width + height - 1,
+        };
+
+        Lines { grid, kind, index: 0, count }
+    }
+
+    fn line(&self, index: usize) -> Line<'a, G> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let (x, y, step_x) = match self.kind {
+            LineKind::Row => (0, index, 1),
+            LineKind::Column => (index, 0, 0),
+            LineKind::Diagonal if index < height => (0, height - 1 - index, 1),
+            LineKind::Diagonal => (index - height + 1, 0, 1),
+            LineKind::AntiDiagonal if index < width => (index, 0, -1),
+            LineKind::AntiDiagonal => (width - 1, index - width + 1, -1),
+        };
+
+        let step_y = if self.kind == LineKind::Row { 0 } else { 1 };
+        let remaining = match self.kind {
+            LineKind::Row => width,
+            LineKind::Column => height,
+            LineKind::Diagonal => (width - x).min(height - y),
+            LineKind::AntiDiagonal => (x + 1).min(height - y),
+        };
+
+        Line { grid: self.grid, x, y, step_x, step_y, remaining }
+    }
+}
+
+impl<'a, G: GridView + ?Sized> Iterator for Lines<'a, G> {
+    type Item = Line<'a, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let line = self.line(self.index);
+        self.index += 1;
+        Some(line)
+    }
+}
+
+pub struct Line<'a, G: ?Sized> {
+    grid: &'a G,
+    x: usize,
+    y: usize,
+    step_x: isize,
+    step_y: usize,
+    remaining: usize,
+}
+
+impl<'a, G: GridView + ?Sized> Iterator for Line<'a, G> {
+    type Item = &'a G::Cell;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let cell = self.grid.cell(self.x, self.y);
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.x = self.x.wrapping_add_signed(self.step_x);
+            self.y += self.step_y;
+        }
+
+        Some(cell)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, G: GridView + ?Sized> ExactSizeIterator for Line<'a, G> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::try_from_lines(&[
+            "abc",
+            "def",
+        ]).unwrap()
+    }
+
+    fn lines_to_strings<'a, G: GridView<Cell=char> + 'a>(lines: Lines<'a, G>) -> Vec<String> {
+        lines.map(|line| line.collect()).collect()
+    }
+
+    #[test]
+    fn test_try_from_lines() {
+        let grid = sample();
+
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Some(&'f'), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(None, Grid::try_from_lines(&["abc", "de"]));
+    }
+
+    #[test]
+    fn test_rows_columns() {
+        let grid = sample();
+
+        assert_eq!(vec!["abc", "def"], lines_to_strings(grid.rows()));
+        assert_eq!(vec!["ad", "be", "cf"], lines_to_strings(grid.columns()));
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let grid = sample();
+
+        assert_eq!(vec!["d", "ae", "bf", "c"], lines_to_strings(grid.diagonals()));
+        assert_eq!(vec!["a", "bd", "ce", "f"], lines_to_strings(grid.anti_diagonals()));
+    }
+
+    #[test]
+    fn test_transpose_flip() {
+        let grid = sample();
+
+        assert_eq!("ad\nbe\ncf", grid.transposed().to_string());
+        assert_eq!("cba\nfed", grid.flipped_horizontally().to_string());
+        assert_eq!("def\nabc", grid.flipped_vertically().to_string());
+    }
+
+    #[test]
+    fn test_rotate() {
+        let grid = sample();
+
+        assert_eq!("da\neb\nfc", grid.rotated(Rotation::Deg90).to_string());
+        assert_eq!("fed\ncba", grid.rotated(Rotation::Deg180).to_string());
+        assert_eq!("cf\nbe\nad", grid.rotated(Rotation::Deg270).to_string());
+        assert_eq!(grid, grid.rotated(Rotation::Deg90).rotated(Rotation::Deg270));
+    }
+
+    #[test]
+    fn test_bounded_addressing() {
+        let grid = sample();
+
+        assert_eq!(Some(&'e'), grid.at(Point::new(1, 1)));
+        assert_eq!(None, grid.at(Point::new(-1, 0)));
+        assert_eq!(None, grid.step(Point::new(2, 1), Point::new(1, 0)));
+    }
+
+    #[test]
+    fn test_wrapping_addressing() {
+        let grid = sample().with_addressing(Addressing::Wrapping);
+
+        assert_eq!(Some(&'f'), grid.at(Point::new(-1, -1)));
+        assert_eq!(Some(&'a'), grid.at(Point::new(3, 4)));
+        assert_eq!(Some(Point::new(0, 0)), grid.step(Point::new(2, 1), Point::new(1, 1)));
+    }
+
+    #[test]
+    fn test_ray() {
+        let bounded = sample();
+        let wrapping = sample().with_addressing(Addressing::Wrapping);
+        let direction = Point::new(1, 1);
+
+        assert_eq!(vec![Point::new(1, 1)], bounded.ray(Point::new(0, 0), direction).collect::<Vec<_>>());
+        assert_eq!(
+            vec![Point::new(1, 1), Point::new(2, 0), Point::new(0, 1), Point::new(1, 0)],
+            wrapping.ray(Point::new(0, 0), direction).take(4).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(Point::new(1, 0)), wrapping.ray(Point::new(0, 0), Point::new(4, 3)).nth(99));
+    }
+
+    #[test]
+    fn test_sub_grid() {
+        let grid = sample();
+
+        assert_eq!("bc\nef", grid.sub_grid(1, 0, 2, 2).unwrap().to_string());
+        assert_eq!(None, grid.sub_grid(2, 0, 2, 2));
+    }
+}
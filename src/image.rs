@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::grid::GridView;
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colors: HashMap<char, Rgb>,
+    fallback: Rgb,
+}
+
+impl Palette {
+    pub fn new(fallback: Rgb) -> Self {
+        Self {
+            colors: HashMap::new(),
+            fallback,
+        }
+    }
+
+    pub fn with(mut self, glyph: char, color: Rgb) -> Self {
+        self.colors.insert(glyph, color);
+        self
+    }
+
+    pub fn color(&self, glyph: char) -> Rgb {
+        self.colors.get(&glyph).copied().unwrap_or(self.fallback)
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::new(WHITE)
+            .with('.', BLACK)
+            .with('#', [128, 128, 128])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    // every grid cell becomes a `scale` x `scale` square
+    pub fn from_grid<G, F>(grid: &G, scale: usize, color: F) -> Self
+    where
+        G: GridView + ?Sized,
+        F: Fn(&G::Cell) -> Rgb,
+    {
+        let width = grid.width() * scale;
+        let height = grid.height() * scale;
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(color(grid.cell(x / scale, y / scale)));
+            }
+        }
+
+        Image { width, height, pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn write_ppm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in &self.pixels {
+            writer.write_all(pixel)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_png<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        const BIT_DEPTH: u8 = 8;
+        const COLOR_TYPE_RGB: u8 = 2;
+        const FILTER_NONE: u8 = 0;
+
+        writer.write_all(&SIGNATURE)?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        header.extend([BIT_DEPTH, COLOR_TYPE_RGB, 0, 0, 0]);
+        write_png_chunk(writer, b"IHDR", &header)?;
+
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(FILTER_NONE);
+            raw.extend(row.iter().flatten());
+        }
+        write_png_chunk(writer, b"IDAT", &zlib_stored(&raw))?;
+
+        write_png_chunk(writer, b"IEND", &[])
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P, format: Format) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            Format::Ppm => self.write_ppm(&mut writer)?,
+            Format::Png => self.write_png(&mut writer)?,
+        }
+        writer.flush()
+    }
+}
+
+// writes numbered frames (`prefix_00000.png`, `prefix_00001.png`, ...) from a simulation loop
+pub struct FrameWriter {
+    directory: PathBuf,
+    prefix: String,
+    format: Format,
+    next_index: usize,
+}
+
+impl FrameWriter {
+    pub fn new<P: AsRef<Path>>(directory: P, prefix: &str, format: Format) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            format,
+            next_index: 0,
+        })
+    }
+
+    pub fn write_frame(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let file_name = format!("{}_{:05}.{}", self.prefix, self.next_index, self.format.extension());
+        let path = self.directory.join(file_name);
+        image.save(&path, self.format)?;
+        self.next_index += 1;
+
+        Ok(path)
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.next_index
+    }
+}
+
+fn write_png_chunk<W: Write>(writer: &mut W, chunk_type: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(chunk_type)?;
+    writer.write_all(data)?;
+
+    let crc = crc32(chunk_type.iter().chain(data));
+    writer.write_all(&crc.to_be_bytes())
+}
+
+// zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const CMF: u8 = 0x78;
+    const FLG: u8 = 0x01;
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut result = vec![CMF, FLG];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+
+    if blocks.peek().is_none() {
+        result.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let len = block.len() as u16;
+        result.push(is_final as u8);
+        result.extend(len.to_le_bytes());
+        result.extend((!len).to_le_bytes());
+        result.extend(block);
+    }
+
+    result.extend(adler32(data).to_be_bytes());
+    result
+}
+
+fn crc32<'a, I: IntoIterator<Item=&'a u8>>(data: I) -> u32 {
+    const POLYNOMIAL: u32 = 0xedb88320;
+
+    let crc = data.into_iter().fold(0xffffffff, |mut crc: u32, byte| {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ POLYNOMIAL } else { crc >> 1 };
+        }
+        crc
+    });
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULO: u32 = 65521;
+
+    let (a, b) = data.iter().fold((1, 0), |(a, b), byte| {
+        let a = (a + *byte as u32) % MODULO;
+        (a, (b + a) % MODULO)
+    });
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn sample() -> Image {
+        let grid = Grid::try_from_lines(&["#.", ".#"]).unwrap();
+        Image::from_grid(&grid, 2, |c| Palette::default().color(*c))
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(0xcbf43926, crc32(b"123456789"));
+        assert_eq!(0x11e60398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn test_from_grid_scaled() {
+        let image = sample();
+        let gray = [128, 128, 128];
+
+        assert_eq!((4, 4), (image.width(), image.height()));
+        assert_eq!(gray, image.pixel(1, 1));
+        assert_eq!(BLACK, image.pixel(2, 1));
+        assert_eq!(gray, image.pixel(3, 3));
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let grid = Grid::try_from_lines(&["#."]).unwrap();
+        let image = Image::from_grid(&grid, 1, |c| Palette::default().color(*c));
+        let mut output = Vec::new();
+
+        image.write_ppm(&mut output).unwrap();
+
+        assert_eq!(b"P6\n2 1\n255\n\x80\x80\x80\x00\x00\x00".to_vec(), output);
+    }
+
+    #[test]
+    fn test_write_png() {
+        let mut output = Vec::new();
+
+        sample().write_png(&mut output).unwrap();
+
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &output[..8]);
+        assert_eq!(b"IHDR", &output[12..16]);
+        assert_eq!([0, 0, 0, 4, 0, 0, 0, 4, 8, 2, 0, 0, 0], output[16..29]);
+        assert_eq!(b"\0\0\0\0IEND\xae\x42\x60\x82", &output[output.len() - 12..]);
+    }
+
+    #[test]
+    fn test_zlib_stored_splits_blocks() {
+        let data = vec![7; 70000];
+
+        let stream = zlib_stored(&data);
+
+        assert_eq!(2 + 5 + 65535 + 5 + 4465 + 4, stream.len());
+        assert_eq!(0, stream[2]);
+        assert_eq!(1, stream[2 + 5 + 65535]);
+    }
+
+    #[test]
+    fn test_frame_writer() {
+        let directory = std::env::temp_dir().join(format!("aoc2024_frames_{}", std::process::id()));
+        let mut frames = FrameWriter::new(&directory, "guard", Format::Ppm).unwrap();
+
+        let first = frames.write_frame(&sample()).unwrap();
+        let second = frames.write_frame(&sample()).unwrap();
+
+        assert_eq!(directory.join("guard_00000.ppm"), first);
+        assert_eq!(directory.join("guard_00001.ppm"), second);
+        assert!(second.exists());
+        assert_eq!(2, frames.frames_written());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
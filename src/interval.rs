@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::ops::{Range, Sub};
+
+// disjoint half-open ranges; touching or overlapping ranges are merged on insert
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: BTreeMap<T, T>,
+}
+
+impl<T: Ord + Copy + Sub<Output=T>> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    // number of disjoint ranges
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = (range.start, range.end);
+
+        if let Some((&previous_start, &previous_end)) = self.ranges.range(..=start).next_back() {
+            if previous_end >= start {
+                start = previous_start;
+                end = end.max(previous_end);
+            }
+        }
+
+        let touched = self.ranges.range(start..=end).map(|(start, _)| *start).collect::<Vec<_>>();
+        for touched_start in touched {
+            end = end.max(self.ranges.remove(&touched_start).unwrap());
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        for (start, end) in remove_overlapping(&mut self.ranges, &range, |end| *end) {
+            if start < range.start {
+                self.ranges.insert(start, range.start);
+            }
+            if end > range.end {
+                self.ranges.insert(range.end, end);
+            }
+        }
+    }
+
+    pub fn contains(&self, point: T) -> bool {
+        self.ranges.range(..=point).next_back().is_some_and(|(_, end)| *end > point)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=Range<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..*end)
+    }
+
+    // start of the first stored range holding `len` points that all lie before `limit`
+    pub fn first_fit(&self, len: T, limit: T) -> Option<T> {
+        self.ranges.iter()
+            .take_while(|(start, _)| **start < limit)
+            .find(|(start, end)| (**end).min(limit) - **start >= len)
+            .map(|(start, _)| *start)
+    }
+
+    // start of the first gap between stored ranges, at or after `from`, holding `len` points before `limit`
+    pub fn first_gap(&self, len: T, from: T, limit: T) -> Option<T> {
+        let fits = |gap_start: T, gap_end: T| gap_start < limit && gap_end.min(limit) - gap_start >= len;
+
+        let mut cursor = from;
+        for (start, end) in self.ranges.range(..limit) {
+            if *end <= cursor {
+                continue;
+            }
+            if *start > cursor && fits(cursor, *start) {
+                return Some(cursor);
+            }
+            cursor = cursor.max(*end);
+        }
+
+        fits(cursor, limit).then_some(cursor)
+    }
+}
+
+impl<T: Ord + Copy + Sub<Output=T>> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Copy + Sub<Output=T>> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item=Range<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+// disjoint half-open ranges with values; touching ranges with equal values are merged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<T, V> {
+    ranges: BTreeMap<T, (T, V)>,
+}
+
+impl<T: Ord + Copy, V: Eq + Clone> RangeMap<T, V> {
+    pub fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // overwrites whatever was stored in `range` before
+    pub fn insert(&mut self, range: Range<T>, value: V) {
+        if range.is_empty() {
+            return;
+        }
+
+        self.remove(range.clone());
+        let (mut start, mut end) = (range.start, range.end);
+
+        if let Some((&previous_start, (previous_end, previous_value))) = self.ranges.range(..start).next_back() {
+            if *previous_end == start && *previous_value == value {
+                start = previous_start;
+            }
+        }
+
+        if let Some((next_end, next_value)) = self.ranges.get(&end) {
+            if *next_value == value {
+                let next_start = end;
+                end = *next_end;
+                self.ranges.remove(&next_start);
+            }
+        }
+
+        self.ranges.insert(start, (end, value));
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        for (start, (end, value)) in remove_overlapping(&mut self.ranges, &range, |(end, _)| *end) {
+            if start < range.start {
+                self.ranges.insert(start, (range.start, value.clone()));
+            }
+            if end > range.end {
+                self.ranges.insert(range.end, (end, value));
+            }
+        }
+    }
+
+    pub fn get(&self, point: T) -> Option<&V> {
+        self.get_range(point).map(|(_, value)| value)
+    }
+
+    pub fn get_range(&self, point: T) -> Option<(Range<T>, &V)> {
+        let (start, (end, value)) = self.ranges.range(..=point).next_back()?;
+        (*end > point).then_some((*start..*end, value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Range<T>, &V)> + '_ {
+        self.ranges.iter().map(|(start, (end, value))| (*start..*end, value))
+    }
+}
+
+impl<T: Ord + Copy, V: Eq + Clone> Default for RangeMap<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Copy, V: Eq + Clone> FromIterator<(Range<T>, V)> for RangeMap<T, V> {
+    fn from_iter<I: IntoIterator<Item=(Range<T>, V)>>(iter: I) -> Self {
+        let mut map = RangeMap::new();
+        for (range, value) in iter {
+            map.insert(range, value);
+        }
+        map
+    }
+}
+
+// takes out every entry overlapping `range`, the caller puts back the parts outside of it
+fn remove_overlapping<T: Ord + Copy, E, F: Fn(&E) -> T>(ranges: &mut BTreeMap<T, E>, range: &Range<T>, end_of: F) -> Vec<(T, E)> {
+    let mut starts = Vec::new();
+
+    if let Some((start, entry)) = ranges.range(..range.start).next_back() {
+        if end_of(entry) > range.start {
+            starts.push(*start);
+        }
+    }
+    starts.extend(ranges.range(range.start..range.end).map(|(start, _)| *start));
+
+    starts.into_iter()
+        .map(|start| (start, ranges.remove(&start).unwrap()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::new();
+
+        set.insert(5..8);
+        set.insert(1..3);
+        set.insert(3..4);
+        set.insert(10..12);
+        set.insert(7..10);
+
+        assert_eq!(vec![1..4, 5..12], set.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set = IntervalSet::from_iter([0..10, 20..30]);
+
+        set.remove(3..5);
+        set.remove(8..22);
+        set.remove(29..40);
+
+        assert_eq!(vec![0..3, 5..8, 22..29], set.iter().collect::<Vec<_>>());
+        assert!(set.contains(0));
+        assert!(!set.contains(3));
+        assert!(!set.contains(29));
+    }
+
+    #[test]
+    fn test_first_fit() {
+        let set = IntervalSet::from_iter([2..5, 8..10, 11..15, 18..19]);
+
+        assert_eq!(Some(2), set.first_fit(3, 14));
+        assert_eq!(Some(2), set.first_fit(2, 4));
+        assert_eq!(Some(11), set.first_fit(4, 100));
+        assert_eq!(None, set.first_fit(4, 14));
+        assert_eq!(None, set.first_fit(5, 100));
+    }
+
+    #[test]
+    fn test_first_gap() {
+        let set = IntervalSet::from_iter([2..5, 8..10, 11..15]);
+
+        assert_eq!(Some(0), set.first_gap(2, 0, 100));
+        assert_eq!(Some(5), set.first_gap(3, 0, 100));
+        assert_eq!(Some(15), set.first_gap(4, 0, 100));
+        assert_eq!(None, set.first_gap(4, 0, 18));
+        assert_eq!(Some(6), set.first_gap(2, 6, 100));
+        assert_eq!(Some(10), set.first_gap(1, 9, 100));
+    }
+
+    #[test]
+    fn test_range_map() {
+        let mut map = RangeMap::new();
+
+        map.insert(0..10, 'a');
+        map.insert(3..5, 'b');
+        map.insert(5..7, 'b');
+        map.insert(12..14, 'a');
+        map.insert(10..12, 'a');
+
+        assert_eq!(
+            vec![(0..3, &'a'), (3..7, &'b'), (7..14, &'a')],
+            map.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&'b'), map.get(6));
+        assert_eq!(None, map.get(14));
+        assert_eq!(Some((7..14, &'a')), map.get_range(7));
+    }
+
+    #[test]
+    fn test_range_map_remove() {
+        let mut map = RangeMap::from_iter([(0..10, 1), (10..20, 2)]);
+
+        map.remove(5..15);
+
+        assert_eq!(vec![(0..5, &1), (15..20, &2)], map.iter().collect::<Vec<_>>());
+    }
+}
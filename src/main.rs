@@ -3,6 +3,25 @@
 use std::{fmt::Display, fs};
 
 mod multimap;
+mod ordered_multimap;
+mod counted_multimap;
+mod bimultimap;
+mod frozen_multimap;
+mod persistent;
+mod graph;
+mod grid;
+mod bitgrid;
+mod render;
+mod image;
+mod parse;
+mod math;
+mod bigint;
+mod cycle;
+mod memo;
+mod disjoint_set;
+mod interval;
+mod priority_queue;
+mod search;
 mod day1;
 mod day2;
 mod day3;
@@ -14,7 +33,12 @@ mod day8;
 mod day9;
 
 fn get_file(path: &str) -> String {
-    fs::read_to_string(path).unwrap()
+    let input = parse::normalize(&fs::read_to_string(path).unwrap());
+    for warning in parse::non_ascii_warnings(&input) {
+        eprintln!("warning: {path}: {warning}");
+    }
+
+    input
 }
 
 fn main() {
@@ -0,0 +1,196 @@
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    a / gcd(a, b) * b
+}
+
+pub fn gcd_slice(numbers: &[u64]) -> u64 {
+    numbers.iter().fold(0, |acc, number| gcd(acc, *number))
+}
+
+pub fn lcm_slice(numbers: &[u64]) -> u64 {
+    numbers.iter().fold(1, |acc, number| lcm(acc, *number))
+}
+
+// returns (g, x, y) such that a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// `None` also for a modulus below 1
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    if modulus < 1 {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g != 1 {
+        return None;
+    }
+
+    Some(x.rem_euclid(modulus))
+}
+
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+
+    result as u64
+}
+
+// solves x = residue (mod modulus) for every pair, moduli don't have to be coprime;
+// returns (x, lcm of moduli) with 0 <= x < lcm, or `None` if there is no solution or a modulus is below 1
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    if congruences.iter().any(|(_, modulus)| *modulus < 1) {
+        return None;
+    }
+
+    let mut result: (i128, i128) = (0, 1);
+
+    for &(residue, modulus) in congruences {
+        let (r1, m1) = result;
+        let (r2, m2) = (residue as i128, modulus as i128);
+        let (g, p, _) = extended_gcd(m1 as i64, m2 as i64);
+        let g = g as i128;
+
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+
+        let lcm = m1 / g * m2;
+        let k = ((r2 - r1) / g * p as i128).rem_euclid(m2 / g);
+        result = ((r1 + m1 * k).rem_euclid(lcm), lcm);
+    }
+
+    Some((i64::try_from(result.0).ok()?, i64::try_from(result.1).ok()?))
+}
+
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut x = (n as f64).sqrt() as u64;
+    while x.checked_mul(x).is_none_or(|square| square > n) {
+        x -= 1;
+    }
+    while (x + 1).checked_mul(x + 1).is_some_and(|square| square <= n) {
+        x += 1;
+    }
+
+    x
+}
+
+pub fn digit_count(n: u64) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+// 12 and 345 give 12345, None on overflow
+pub fn concat_digits(a: u64, b: u64) -> Option<u64> {
+    10u64.checked_pow(digit_count(b))?.checked_mul(a)?.checked_add(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(6, gcd(12, 18));
+        assert_eq!(5, gcd(0, 5));
+        assert_eq!(36, lcm(12, 18));
+        assert_eq!(0, lcm(0, 18));
+        assert_eq!(4, gcd_slice(&[8, 12, 20]));
+        assert_eq!(120, lcm_slice(&[8, 12, 20]));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240, 46);
+
+        assert_eq!(2, g);
+        assert_eq!(g, 240 * x + 46 * y);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(Some(7), mod_inverse(-3, 11));
+        assert_eq!(None, mod_inverse(6, 9));
+        assert_eq!(None, mod_inverse(3, 0));
+        assert_eq!(None, mod_inverse(3, -11));
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(445, mod_pow(4, 13, 497));
+        assert_eq!(0, mod_pow(5, 0, 1));
+        assert_eq!(1, mod_pow(u64::MAX, 0, u64::MAX));
+        assert_eq!(mod_pow(3, 200, 1_000_000_007), mod_pow(9, 100, 1_000_000_007));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]));
+        assert_eq!(None, crt(&[(1, 4), (2, 6)]));
+        assert_eq!(None, crt(&[(2, 3), (0, 0)]));
+        assert_eq!(None, crt(&[(2, -3)]));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(0, isqrt(0));
+        assert_eq!(4, isqrt(24));
+        assert_eq!(5, isqrt(25));
+        assert_eq!(u32::MAX as u64, isqrt(u64::MAX));
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(1, digit_count(0));
+        assert_eq!(3, digit_count(100));
+        assert_eq!(20, digit_count(u64::MAX));
+        assert_eq!(Some(12345), concat_digits(12, 345));
+        assert_eq!(Some(150), concat_digits(15, 0));
+        assert_eq!(None, concat_digits(u64::MAX, 1));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: MemoStats,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: MemoStats::default(),
+        }
+    }
+
+    // `compute` gets the memo back, so it can recurse through it
+    pub fn get_or_insert_with<F: FnOnce(&mut Self) -> V>(&mut self, key: K, compute: F) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    // drops cached values, stats are kept
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = MemoStats::default();
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type Body<'a, K, V> = Box<dyn Fn(&mut dyn FnMut(K) -> V, K) -> V + 'a>;
+
+// a memoised recursive function, the body recurses through its first argument:
+// `MemoFn::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) })`
+pub struct MemoFn<'a, K, V> {
+    memo: Memo<K, V>,
+    body: Body<'a, K, V>,
+}
+
+impl<'a, K: Eq + Hash + Clone, V: Clone> MemoFn<'a, K, V> {
+    pub fn new<F: Fn(&mut dyn FnMut(K) -> V, K) -> V + 'a>(body: F) -> Self {
+        Self {
+            memo: Memo::new(),
+            body: Box::new(body),
+        }
+    }
+
+    pub fn call(&mut self, key: K) -> V {
+        Self::call_with(&mut self.memo, &self.body, key)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.memo.stats()
+    }
+
+    pub fn clear(&mut self) {
+        self.memo.clear();
+    }
+
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+
+    fn call_with(memo: &mut Memo<K, V>, body: &Body<'a, K, V>, key: K) -> V {
+        memo.get_or_insert_with(key.clone(), |memo| {
+            let mut recurse = |key: K| Self::call_with(memo, body, key);
+            body(&mut recurse, key)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+
+        memo.get_or_insert_with(n, |memo| fib(memo, n - 1) + fib(memo, n - 2))
+    }
+
+    #[test]
+    fn test_memo_recursive_fn() {
+        let mut memo = Memo::new();
+
+        assert_eq!(2880067194370816120, fib(&mut memo, 90));
+        assert_eq!(89, memo.len());
+        assert_eq!(MemoStats { hits: 87, misses: 89 }, memo.stats());
+    }
+
+    #[test]
+    fn test_memo_clear() {
+        let mut memo = Memo::new();
+        fib(&mut memo, 10);
+
+        memo.clear();
+
+        assert!(memo.is_empty());
+        assert_eq!(None, memo.get(&10));
+        assert_eq!(MemoStats { hits: 7, misses: 9 }, memo.stats());
+
+        memo.reset_stats();
+        assert_eq!(MemoStats::default(), memo.stats());
+    }
+
+    #[test]
+    fn test_memo_fn_closure() {
+        let mut fib = MemoFn::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+
+        assert_eq!(2880067194370816120, fib.call(90));
+        assert_eq!(MemoStats { hits: 88, misses: 91 }, fib.stats());
+        assert_eq!(2880067194370816120, fib.call(90));
+        assert_eq!(89, fib.stats().hits);
+    }
+
+    #[test]
+    fn test_memo_fn_captures() {
+        // ways to build `target` from the given pieces
+        let pieces = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+        let target = "brwrr";
+
+        let mut count = MemoFn::new(|count, start: usize| -> u64 {
+            if start == target.len() {
+                return 1;
+            }
+
+            pieces.iter()
+                .filter(|piece| target[start..].starts_with(*piece))
+                .map(|piece| count(start + piece.len()))
+                .sum()
+        });
+
+        assert_eq!(2, count.call(0));
+        count.clear();
+        assert!(count.memo().is_empty());
+    }
+}
@@ -1,11 +1,17 @@
 use std::borrow::Borrow;
 use std::collections::{hash_map, HashMap, HashSet};
+use std::collections::hash_map::RandomState;
 use std::collections::hash_set;
-use std::hash::Hash;
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct MultiMap<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash> {
-    map: HashMap<K, HashSet<V>>,
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::str::FromStr;
+use crate::frozen_multimap::FrozenMultiMap;
+use crate::parse::{self, ParseError};
+
+// value groups are built with a clone of the map's hasher
+#[derive(Clone, Debug)]
+pub struct MultiMap<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash, S = RandomState> {
+    map: HashMap<K, HashSet<V, S>, S>,
 }
 
 impl<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash> MultiMap<K, V> {
@@ -15,15 +21,48 @@ impl<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash> MultiMap<K, V> {
         }
     }
 
+    // capacity for `capacity` distinct keys
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash, S: BuildHasher + Clone> MultiMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
     pub fn insert(&mut self, key: K, value: V) {
         if let Some(values) = self.map.get_mut(&key) {
             values.insert(value);
         } else {
-            self.map.insert(key, HashSet::from([value]));
+            let mut values = HashSet::with_hasher(self.map.hasher().clone());
+            values.insert(value);
+            self.map.insert(key, values);
         }
     }
 
-    pub fn remove(&mut self, key: &K, value: &V) {
+    pub fn remove<Q, R>(&mut self, key: &Q, value: &R)
+    where
+        K: Borrow<Q>,
+        V: Borrow<R>,
+        Q: ?Sized + Hash + Eq,
+        R: ?Sized + Hash + Eq,
+    {
         if let Some(values) = self.map.get_mut(key) {
             values.remove(value);
             if values.is_empty() {
@@ -32,15 +71,73 @@ impl<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash> MultiMap<K, V> {
         }
     }
 
-    pub fn get(&self, key: &K) -> Option<hash_set::Iter<'_, V>> {
-        self.map.get(key.borrow()).map(|set| set.iter())
+    pub fn get<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<hash_set::Iter<'_, V>>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.get(key).map(|set| set.iter())
+    }
+
+    // like `get`, but a missing key yields no values instead of `None`
+    pub fn get_or_empty<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> ValuesGroupIter<'_, V>
+    where
+        K: Borrow<Q>,
+    {
+        ValuesGroupIter {
+            iter: self.map.get(key).map(|set| set.iter()),
+        }
+    }
+
+    // number of (key, value) pairs
+    pub fn len(&self) -> usize {
+        self.map.values().map(|set| set.len()).sum()
+    }
+
+    // number of distinct keys
+    pub fn key_len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn keys(&self) -> hash_map::Keys<'_, K, HashSet<V, S>> {
+        self.map.keys()
+    }
+
+    pub fn remove_key<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<HashSet<V, S>>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.remove(key)
+    }
+
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, mut predicate: F) {
+        self.map.retain(|key, set| {
+            set.retain(|value| predicate(key, value));
+            !set.is_empty()
+        });
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        Entry {
+            map: &mut self.map,
+            key,
+        }
     }
 
-    pub fn contains(&self, key: &K, value: &V) -> bool {
+    pub fn contains<Q, R>(&self, key: &Q, value: &R) -> bool
+    where
+        K: Borrow<Q>,
+        V: Borrow<R>,
+        Q: ?Sized + Hash + Eq,
+        R: ?Sized + Hash + Eq,
+    {
         self.map.get(key).is_some_and(|set| set.contains(value))
     }
 
-    pub fn iter(&self) -> Iter<K, V> {
+    pub fn iter(&self) -> Iter<'_, K, V, S> {
         Iter {
             current_key: None,
             map_iter: self.map.iter(),
@@ -48,19 +145,187 @@ impl<K: PartialEq + Eq + Hash, V: PartialEq + Eq + Hash> MultiMap<K, V> {
         }
     }
 
-    pub fn values(&self) -> Values<K, V> {
+    pub fn values(&self) -> Values<'_, K, V, S> {
         Values {
             iter: self.iter()
         }
     }
 
-    pub fn grouped_values(&self) -> GroupedValues<K, V> {
+    pub fn grouped_values(&self) -> GroupedValues<'_, K, V, S> {
         GroupedValues {
             map_iter: self.map.iter(),
         }
     }
 }
 
+impl<K: Eq + Hash + Clone, V: Eq + Hash, S: BuildHasher + Clone> MultiMap<K, V, S> {
+    pub fn drain(&mut self) -> Drain<'_, K, V, S> {
+        IntoIter {
+            map_iter: self.map.drain(),
+            current: None,
+        }
+    }
+}
+
+// set algebra over (key, value) pairs, results use a clone of `self`'s hasher
+impl<K: Eq + Hash + Clone, V: Eq + Hash + Clone, S: BuildHasher + Clone> MultiMap<K, V, S> {
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.extend(other.iter().map(|(key, value)| (key.clone(), value.clone())));
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.filtered(|key, value| other.contains(key, value))
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.filtered(|key, value| !other.contains(key, value))
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        let mut result = self.difference(other);
+        result.extend(other.iter()
+            .filter(|(key, value)| !self.contains(*key, *value))
+            .map(|(key, value)| (key.clone(), value.clone())));
+        result
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.iter().all(|(key, value)| other.contains(key, value))
+    }
+
+    // value -> keys
+    pub fn invert(&self) -> MultiMap<V, K, S> {
+        let mut result = MultiMap::with_hasher(self.hasher().clone());
+        result.extend(self.iter().map(|(key, value)| (value.clone(), key.clone())));
+        result
+    }
+
+    fn filtered<F: Fn(&K, &V) -> bool>(&self, predicate: F) -> Self {
+        let mut result = Self::with_hasher(self.hasher().clone());
+        result.extend(self.iter()
+            .filter(|(key, value)| predicate(key, value))
+            .map(|(key, value)| (key.clone(), value.clone())));
+        result
+    }
+}
+
+// line layout for `MultiMap::text` and `MultiMap::parse_text`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat<'a> {
+    // `47|53`, one line per pair
+    Pairs(&'a str),
+    // `47: 13 53 61`, one line per key
+    Grouped,
+}
+
+impl<K: Eq + Hash + Ord + Display, V: Eq + Hash + Ord + Display, S: BuildHasher + Clone> MultiMap<K, V, S> {
+    // lines sorted by key and then value, so equal maps always give equal text
+    pub fn text(&self, format: TextFormat) -> String {
+        let mut groups = self.map.iter()
+            .map(|(key, values)| {
+                let mut values = values.iter().collect::<Vec<_>>();
+                values.sort();
+                (key, values)
+            })
+            .collect::<Vec<_>>();
+        groups.sort_by_key(|(key, _)| *key);
+
+        let mut result = String::new();
+        for (key, values) in groups {
+            match format {
+                TextFormat::Pairs(separator) => {
+                    for value in values {
+                        result += &format!("{key}{separator}{value}\n");
+                    }
+                }
+                TextFormat::Grouped => {
+                    let values = values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+                    result += &format!("{key}: {}\n", values.join(" "));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<K: Eq + Hash + FromStr, V: Eq + Hash + FromStr, S: BuildHasher + Clone + Default> MultiMap<K, V, S>
+where
+    K::Err: Display,
+    V::Err: Display,
+{
+    // blank lines are skipped
+    pub fn parse_text(input: &str, format: TextFormat) -> Result<Self, ParseError> {
+        let mut map = Self::default();
+
+        for line in parse::lines(input) {
+            match format {
+                TextFormat::Pairs(separator) => {
+                    let (key, value) = line.key_value(separator)?;
+                    map.insert(key, value);
+                }
+                TextFormat::Grouped => {
+                    let (key, values) = line.split_once(":")?;
+                    let values = values.fields::<V>()?;
+                    if values.is_empty() {
+                        return Err(line.error(line.text.chars().count() + 1, "expected values after `:`".to_string()));
+                    }
+
+                    let hasher = map.hasher().clone();
+                    map.map.entry(key.parse()?).or_insert_with(|| HashSet::with_hasher(hasher)).extend(values);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+// grouped form, so `Display` and `FromStr` round-trip
+impl<K: Eq + Hash + Ord + Display, V: Eq + Hash + Ord + Display, S: BuildHasher + Clone> Display for MultiMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text(TextFormat::Grouped))
+    }
+}
+
+impl<K: Eq + Hash + FromStr, V: Eq + Hash + FromStr, S: BuildHasher + Clone + Default> FromStr for MultiMap<K, V, S>
+where
+    K::Err: Display,
+    V::Err: Display,
+{
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse_text(input, TextFormat::Grouped)
+    }
+}
+
+impl<K: Eq + Hash + Ord, V: Eq + Hash + Ord, S> MultiMap<K, V, S> {
+    // read-only copy in a contiguous layout, for maps that are only queried after parsing
+    pub fn freeze(self) -> FrozenMultiMap<K, V> {
+        FrozenMultiMap::from_groups(self.map.into_iter()
+            .map(|(key, values)| (key, values.into_iter().collect()))
+            .collect())
+    }
+}
+
+// written out because a derive would also require `S: PartialEq`, which `RandomState` is not
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher> PartialEq for MultiMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher> Eq for MultiMap<K, V, S> {}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone + Default> Default for MultiMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
 impl<K: Eq + Hash, V: Eq + Hash, const N: usize> From<[(K, V); N]> for MultiMap<K, V> {
     fn from(keys_values: [(K, V); N]) -> Self {
         let mut map = Self::new();
@@ -73,23 +338,112 @@ impl<K: Eq + Hash, V: Eq + Hash, const N: usize> From<[(K, V); N]> for MultiMap<
     }
 }
 
-impl<K: Eq + Hash, V: Eq + Hash> FromIterator<(K, V)> for MultiMap<K, V> {
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone + Default> FromIterator<(K, V)> for MultiMap<K, V, S> {
     fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
-        let mut map = MultiMap::new();
+        let mut map = MultiMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone> Extend<(K, V)> for MultiMap<K, V, S> {
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
         for (key, value) in iter {
-            map.insert(key, value);
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone> IntoIterator for &'a MultiMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Eq + Hash, S> IntoIterator for MultiMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, hash_map::IntoIter<K, HashSet<V, S>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            map_iter: self.map.into_iter(),
+            current: None,
         }
-        map
     }
 }
 
-pub struct Iter<'a, K: 'a, V: 'a> {
+// values of a single key, inserting and removing through it keeps the map free of empty groups
+pub struct Entry<'a, K: 'a, V: 'a, S: 'a = RandomState> {
+    map: &'a mut HashMap<K, HashSet<V, S>, S>,
+    key: K,
+}
+
+impl<'a, K: Eq + Hash + Clone + 'a, V: Eq + Hash + 'a, S: BuildHasher + Clone + 'a> Entry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    // returns false if the value was already there
+    pub fn insert(&mut self, value: V) -> bool {
+        if let Some(values) = self.map.get_mut(&self.key) {
+            values.insert(value)
+        } else {
+            let mut values = HashSet::with_hasher(self.map.hasher().clone());
+            values.insert(value);
+            self.map.insert(self.key.clone(), values);
+            true
+        }
+    }
+
+    pub fn remove(&mut self, value: &V) -> bool {
+        let Some(values) = self.map.get_mut(&self.key) else {
+            return false;
+        };
+
+        let removed = values.remove(value);
+        if values.is_empty() {
+            self.map.remove(&self.key);
+        }
+        removed
+    }
+
+    pub fn contains(&self, value: &V) -> bool {
+        self.map.get(&self.key).is_some_and(|set| set.contains(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.get(&self.key).map_or(0, |set| set.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.map.contains_key(&self.key)
+    }
+
+    pub fn values(&self) -> ValuesGroupIter<'_, V> {
+        ValuesGroupIter {
+            iter: self.map.get(&self.key).map(|set| set.iter()),
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash + Clone + 'a, V: Eq + Hash + 'a, S: BuildHasher + Clone + 'a> Extend<V> for Entry<'a, K, V, S> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+pub struct Iter<'a, K: 'a, V: 'a, S: 'a = RandomState> {
     current_key: Option<&'a K>,
-    map_iter: hash_map::Iter<'a, K, HashSet<V>>,
+    map_iter: hash_map::Iter<'a, K, HashSet<V, S>>,
     set_iter: Option<hash_set::Iter<'a, V>>,
 }
 
-impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
+impl<'a, K: 'a, V: 'a, S: 'a> Iterator for Iter<'a, K, V, S> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -112,11 +466,11 @@ impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
     }
 }
 
-pub struct Values<'a, K: 'a, V: 'a> {
-    iter: Iter<'a, K, V>,
+pub struct Values<'a, K: 'a, V: 'a, S: 'a = RandomState> {
+    iter: Iter<'a, K, V, S>,
 }
 
-impl<'a, K: 'a, V: 'a> Iterator for Values<'a, K, V> {
+impl<'a, K: 'a, V: 'a, S: 'a> Iterator for Values<'a, K, V, S> {
     type Item = &'a V;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -125,26 +479,55 @@ impl<'a, K: 'a, V: 'a> Iterator for Values<'a, K, V> {
 }
 
 pub struct ValuesGroupIter<'a, V: 'a> {
-    iter: hash_set::Iter<'a, V>,
+    iter: Option<hash_set::Iter<'a, V>>,
 }
 
 impl<'a, V: 'a> Iterator for ValuesGroupIter<'a, V> {
     type Item = &'a V;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+        self.iter.as_mut()?.next()
     }
 }
 
-pub struct GroupedValues<'a, K: 'a, V: 'a> {
-    map_iter: hash_map::Iter<'a, K, HashSet<V>>,
+// owned pairs, the key is cloned for every value but the last
+pub struct IntoIter<K, V, I = hash_map::IntoIter<K, HashSet<V>>> {
+    map_iter: I,
+    current: Option<(K, hash_set::IntoIter<V>)>,
 }
 
-impl<'a, K: 'a, V: 'a> Iterator for GroupedValues<'a, K, V> {
+pub type Drain<'a, K, V, S = RandomState> = IntoIter<K, V, hash_map::Drain<'a, K, HashSet<V, S>>>;
+
+impl<K: Clone, V, S, I: Iterator<Item=(K, HashSet<V, S>)>> Iterator for IntoIter<K, V, I> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, set_iter)) = &mut self.current {
+                if let Some(value) = set_iter.next() {
+                    if set_iter.len() == 0 {
+                        let (key, _) = self.current.take()?;
+                        return Some((key, value));
+                    }
+                    return Some((key.clone(), value));
+                }
+            }
+
+            let (key, set) = self.map_iter.next()?;
+            self.current = Some((key, set.into_iter()));
+        }
+    }
+}
+
+pub struct GroupedValues<'a, K: 'a, V: 'a, S: 'a = RandomState> {
+    map_iter: hash_map::Iter<'a, K, HashSet<V, S>>,
+}
+
+impl<'a, K: 'a, V: 'a, S: 'a> Iterator for GroupedValues<'a, K, V, S> {
     type Item = (&'a K, ValuesGroupIter<'a, V>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.map_iter.next().map(|(key, set)| (key, ValuesGroupIter { iter: set.iter() }))
+        self.map_iter.next().map(|(key, set)| (key, ValuesGroupIter { iter: Some(set.iter()) }))
     }
 }
 
@@ -285,4 +668,180 @@ mod tests {
         
         assert_eq!(expected_values, actual_values);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_len_keys() {
+        let map = MultiMap::from([
+            (11, 22),
+            (11, 33),
+            (22, 44),
+        ]);
+
+        let mut keys = map.keys().copied().collect::<Vec<_>>();
+        keys.sort();
+
+        assert_eq!(3, map.len());
+        assert_eq!(2, map.key_len());
+        assert_eq!(vec![11, 22], keys);
+        assert!(!map.is_empty());
+        assert!(MultiMap::<i32, i32>::new().is_empty());
+    }
+
+    #[test]
+    fn test_get_or_empty() {
+        let map = MultiMap::from([(11, 22)]);
+
+        assert_eq!(vec![&22], map.get_or_empty(&11).collect::<Vec<_>>());
+        assert_eq!(None, map.get_or_empty(&22).next());
+    }
+
+    #[test]
+    fn test_remove_key_retain() {
+        let mut map = MultiMap::from([
+            (11, 22),
+            (11, 33),
+            (22, 44),
+            (33, 55),
+        ]);
+
+        assert_eq!(Some(HashSet::from([44])), map.remove_key(&22));
+        assert_eq!(None, map.remove_key(&22));
+
+        map.retain(|_, value| *value != 33 && *value != 55);
+
+        assert_eq!(MultiMap::from([(11, 22)]), map);
+    }
+
+    #[test]
+    fn test_drain_extend() {
+        let mut map = MultiMap::from([
+            (11, 22),
+            (11, 33),
+        ]);
+
+        let mut drained = map.drain().collect::<Vec<_>>();
+        drained.sort();
+
+        assert_eq!(vec![(11, 22), (11, 33)], drained);
+        assert!(map.is_empty());
+
+        map.extend(drained);
+        map.extend([(22, 44)]);
+
+        assert_eq!(MultiMap::from([(11, 22), (11, 33), (22, 44)]), map);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let map = MultiMap::from([
+            (11, 22),
+            (11, 33),
+            (22, 44),
+        ]);
+
+        let mut borrowed = (&map).into_iter().map(|(key, value)| (*key, *value)).collect::<Vec<_>>();
+        let mut owned = map.into_iter().collect::<Vec<_>>();
+        borrowed.sort();
+        owned.sort();
+
+        assert_eq!(vec![(11, 22), (11, 33), (22, 44)], owned);
+        assert_eq!(owned, borrowed);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map = MultiMap::new();
+
+        let mut entry = map.entry(11);
+        assert!(entry.is_empty());
+        assert!(entry.insert(22));
+        assert!(!entry.insert(22));
+        entry.extend([33, 44]);
+        assert!(entry.remove(&44));
+        assert!(entry.contains(&33));
+        assert_eq!(2, entry.len());
+
+        let mut entry = map.entry(22);
+        entry.insert(55);
+        entry.remove(&55);
+
+        assert_eq!(MultiMap::from([(11, 22), (11, 33)]), map);
+    }
+
+    #[test]
+    fn test_borrowed_lookups() {
+        let mut map = MultiMap::from([
+            ("a".to_string(), "x".to_string()),
+            ("a".to_string(), "y".to_string()),
+        ]);
+
+        assert!(map.contains("a", "x"));
+        assert_eq!(2, map.get("a").unwrap().len());
+
+        map.remove("a", "x");
+        assert_eq!(vec!["y"], map.get_or_empty("a").collect::<Vec<_>>());
+        assert!(map.remove_key("a").is_some());
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_custom_hasher() {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+
+        let mut map = MultiMap::with_capacity_and_hasher(4, BuildHasherDefault::<DefaultHasher>::default());
+        map.insert(11, 22);
+        map.entry(11).insert(33);
+
+        let collected: MultiMap<i32, i32, BuildHasherDefault<DefaultHasher>> = [(11, 22), (11, 33)].into_iter().collect();
+
+        assert_eq!(collected, map);
+        assert!(MultiMap::<i32, i32>::with_capacity(4).is_empty());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = MultiMap::from([(1, 10), (1, 11), (2, 20)]);
+        let b = MultiMap::from([(1, 11), (2, 21), (3, 30)]);
+
+        assert_eq!(MultiMap::from([(1, 10), (1, 11), (2, 20), (2, 21), (3, 30)]), a.union(&b));
+        assert_eq!(MultiMap::from([(1, 11)]), a.intersection(&b));
+        assert_eq!(MultiMap::from([(1, 10), (2, 20)]), a.difference(&b));
+        assert_eq!(MultiMap::from([(1, 10), (2, 20), (2, 21), (3, 30)]), a.symmetric_difference(&b));
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+    }
+
+    #[test]
+    fn test_invert() {
+        let map = MultiMap::from([(1, 'a'), (2, 'a'), (2, 'b')]);
+
+        assert_eq!(MultiMap::from([('a', 1), ('a', 2), ('b', 2)]), map.invert());
+    }
+
+    #[test]
+    fn test_text() {
+        let map = MultiMap::from([(61, 13), (47, 61), (47, 13), (47, 53)]);
+
+        assert_eq!("47|13\n47|53\n47|61\n61|13\n", map.text(TextFormat::Pairs("|")));
+        assert_eq!("47: 13 53 61\n61: 13\n", map.to_string());
+    }
+
+    #[test]
+    fn test_parse_text() {
+        let pairs = "47|53\n97|13\n\n47|13\n";
+        let expected = MultiMap::from([(47, 53), (47, 13), (97, 13)]);
+
+        assert_eq!(Ok(expected.clone()), MultiMap::parse_text(pairs, TextFormat::Pairs("|")));
+        assert_eq!(Ok(expected.clone()), expected.to_string().parse());
+
+        let error = "47: 53 x".parse::<MultiMap<u8, u8>>().unwrap_err();
+        assert_eq!((1, 8), (error.line, error.column));
+        assert!(error.message.contains("`x`"));
+
+        let error = "47: 53\n97:".parse::<MultiMap<u8, u8>>().unwrap_err();
+        assert_eq!((2, 4), (error.line, error.column));
+
+        let error = MultiMap::<u8, u8>::parse_text("47|53\n\n97|1x", TextFormat::Pairs("|")).unwrap_err();
+        assert_eq!((3, 4), (error.line, error.column));
+    }
+}
@@ -0,0 +1,662 @@
+use std::borrow::Borrow;
+use std::collections::{btree_map, btree_set, BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+use std::str::FromStr;
+use crate::frozen_multimap::FrozenMultiMap;
+use crate::multimap::TextFormat;
+use crate::parse::{self, ParseError};
+
+// MultiMap with sorted keys and values, so iteration order is reproducible
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrderedMultiMap<K: Ord, V: Ord> {
+    map: BTreeMap<K, BTreeSet<V>>,
+}
+
+impl<K: Ord, V: Ord> OrderedMultiMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            map: Default::default(),
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().insert(value);
+    }
+
+    pub fn remove<Q, R>(&mut self, key: &Q, value: &R)
+    where
+        K: Borrow<Q>,
+        V: Borrow<R>,
+        Q: ?Sized + Ord,
+        R: ?Sized + Ord,
+    {
+        if let Some(values) = self.map.get_mut(key) {
+            values.remove(value);
+            if values.is_empty() {
+                self.map.remove(key);
+            }
+        }
+    }
+
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<btree_set::Iter<'_, V>>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.get(key).map(|set| set.iter())
+    }
+
+    // like `get`, but a missing key yields no values instead of `None`
+    pub fn get_or_empty<Q: ?Sized + Ord>(&self, key: &Q) -> ValuesGroupIter<'_, V>
+    where
+        K: Borrow<Q>,
+    {
+        ValuesGroupIter {
+            iter: self.map.get(key).map(|set| set.iter()),
+        }
+    }
+
+    // number of (key, value) pairs
+    pub fn len(&self) -> usize {
+        self.map.values().map(|set| set.len()).sum()
+    }
+
+    // number of distinct keys
+    pub fn key_len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn keys(&self) -> btree_map::Keys<'_, K, BTreeSet<V>> {
+        self.map.keys()
+    }
+
+    pub fn remove_key<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<BTreeSet<V>>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.remove(key)
+    }
+
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, mut predicate: F) {
+        self.map.retain(|key, set| {
+            set.retain(|value| predicate(key, value));
+            !set.is_empty()
+        });
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        Entry {
+            map: &mut self.map,
+            key,
+        }
+    }
+
+    pub fn contains<Q, R>(&self, key: &Q, value: &R) -> bool
+    where
+        K: Borrow<Q>,
+        V: Borrow<R>,
+        Q: ?Sized + Ord,
+        R: ?Sized + Ord,
+    {
+        self.map.get(key).is_some_and(|set| set.contains(value))
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            map_iter: self.map.iter(),
+            current: None,
+        }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.iter()
+        }
+    }
+
+    pub fn grouped_values(&self) -> GroupedValues<'_, K, V> {
+        GroupedValues {
+            map_iter: self.map.iter(),
+            entries: PhantomData,
+        }
+    }
+
+    // pairs whose key lies in `range`, in order
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V, btree_map::Range<'_, K, BTreeSet<V>>> {
+        Iter {
+            map_iter: self.map.range(range),
+            current: None,
+        }
+    }
+
+    pub fn grouped_range<R: RangeBounds<K>>(&self, range: R) -> GroupedValues<'_, K, V, btree_map::Range<'_, K, BTreeSet<V>>> {
+        GroupedValues {
+            map_iter: self.map.range(range),
+            entries: PhantomData,
+        }
+    }
+
+    pub fn first_key(&self) -> Option<&K> {
+        self.map.keys().next()
+    }
+
+    pub fn last_key(&self) -> Option<&K> {
+        self.map.keys().next_back()
+    }
+}
+
+impl<K: Ord + Clone, V: Ord> OrderedMultiMap<K, V> {
+    // empties the map, pairs come out in order
+    pub fn drain(&mut self) -> IntoIter<K, V> {
+        IntoIter {
+            map_iter: std::mem::take(&mut self.map).into_iter(),
+            current: None,
+        }
+    }
+}
+
+// set algebra over (key, value) pairs
+impl<K: Ord + Clone, V: Ord + Clone> OrderedMultiMap<K, V> {
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.extend(other.iter().map(|(key, value)| (key.clone(), value.clone())));
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.filtered(|key, value| other.contains(key, value))
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.filtered(|key, value| !other.contains(key, value))
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        let mut result = self.difference(other);
+        result.extend(other.iter()
+            .filter(|(key, value)| !self.contains(*key, *value))
+            .map(|(key, value)| (key.clone(), value.clone())));
+        result
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.iter().all(|(key, value)| other.contains(key, value))
+    }
+
+    // value -> keys
+    pub fn invert(&self) -> OrderedMultiMap<V, K> {
+        self.iter().map(|(key, value)| (value.clone(), key.clone())).collect()
+    }
+
+    fn filtered<F: Fn(&K, &V) -> bool>(&self, predicate: F) -> Self {
+        self.iter()
+            .filter(|(key, value)| predicate(key, value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+// same layouts as `MultiMap::text` and `MultiMap::parse_text`
+impl<K: Ord + Display, V: Ord + Display> OrderedMultiMap<K, V> {
+    pub fn text(&self, format: TextFormat) -> String {
+        let mut result = String::new();
+        for (key, values) in &self.map {
+            match format {
+                TextFormat::Pairs(separator) => {
+                    for value in values {
+                        result += &format!("{key}{separator}{value}\n");
+                    }
+                }
+                TextFormat::Grouped => {
+                    let values = values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+                    result += &format!("{key}: {}\n", values.join(" "));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<K: Ord + FromStr, V: Ord + FromStr> OrderedMultiMap<K, V>
+where
+    K::Err: Display,
+    V::Err: Display,
+{
+    // blank lines are skipped
+    pub fn parse_text(input: &str, format: TextFormat) -> Result<Self, ParseError> {
+        let mut map = Self::new();
+
+        for line in parse::lines(input) {
+            match format {
+                TextFormat::Pairs(separator) => {
+                    let (key, value) = line.key_value(separator)?;
+                    map.insert(key, value);
+                }
+                TextFormat::Grouped => {
+                    let (key, values) = line.split_once(":")?;
+                    let values = values.fields::<V>()?;
+                    if values.is_empty() {
+                        return Err(line.error(line.text.chars().count() + 1, "expected values after `:`".to_string()));
+                    }
+
+                    map.map.entry(key.parse()?).or_default().extend(values);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+// grouped form, so `Display` and `FromStr` round-trip
+impl<K: Ord + Display, V: Ord + Display> Display for OrderedMultiMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text(TextFormat::Grouped))
+    }
+}
+
+impl<K: Ord + FromStr, V: Ord + FromStr> FromStr for OrderedMultiMap<K, V>
+where
+    K::Err: Display,
+    V::Err: Display,
+{
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse_text(input, TextFormat::Grouped)
+    }
+}
+
+impl<K: Ord, V: Ord> OrderedMultiMap<K, V> {
+    // read-only copy in a contiguous layout, for maps that are only queried after parsing
+    pub fn freeze(self) -> FrozenMultiMap<K, V> {
+        FrozenMultiMap::from_groups(self.map.into_iter()
+            .map(|(key, values)| (key, values.into_iter().collect()))
+            .collect())
+    }
+}
+
+// written out because a derive would also require `K: Default, V: Default`
+impl<K: Ord, V: Ord> Default for OrderedMultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V: Ord, const N: usize> From<[(K, V); N]> for OrderedMultiMap<K, V> {
+    fn from(keys_values: [(K, V); N]) -> Self {
+        keys_values.into_iter().collect()
+    }
+}
+
+impl<K: Ord, V: Ord> FromIterator<(K, V)> for OrderedMultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        let mut map = OrderedMultiMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl<K: Ord, V: Ord> Extend<(K, V)> for OrderedMultiMap<K, V> {
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K: Ord, V: Ord> IntoIterator for &'a OrderedMultiMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Ord + Clone, V: Ord> IntoIterator for OrderedMultiMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            map_iter: self.map.into_iter(),
+            current: None,
+        }
+    }
+}
+
+// values of a single key, inserting and removing through it keeps the map free of empty groups
+pub struct Entry<'a, K: 'a, V: 'a> {
+    map: &'a mut BTreeMap<K, BTreeSet<V>>,
+    key: K,
+}
+
+impl<'a, K: Ord + Clone + 'a, V: Ord + 'a> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    // returns false if the value was already there
+    pub fn insert(&mut self, value: V) -> bool {
+        if let Some(values) = self.map.get_mut(&self.key) {
+            values.insert(value)
+        } else {
+            self.map.insert(self.key.clone(), BTreeSet::from([value]));
+            true
+        }
+    }
+
+    pub fn remove(&mut self, value: &V) -> bool {
+        let Some(values) = self.map.get_mut(&self.key) else {
+            return false;
+        };
+
+        let removed = values.remove(value);
+        if values.is_empty() {
+            self.map.remove(&self.key);
+        }
+        removed
+    }
+
+    pub fn contains(&self, value: &V) -> bool {
+        self.map.get(&self.key).is_some_and(|set| set.contains(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.get(&self.key).map_or(0, |set| set.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.map.contains_key(&self.key)
+    }
+
+    pub fn values(&self) -> ValuesGroupIter<'_, V> {
+        ValuesGroupIter {
+            iter: self.map.get(&self.key).map(|set| set.iter()),
+        }
+    }
+}
+
+impl<'a, K: Ord + Clone + 'a, V: Ord + 'a> Extend<V> for Entry<'a, K, V> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+pub struct Iter<'a, K: 'a, V: 'a, I = btree_map::Iter<'a, K, BTreeSet<V>>> {
+    map_iter: I,
+    current: Option<(&'a K, btree_set::Iter<'a, V>)>,
+}
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item=(&'a K, &'a BTreeSet<V>)>> Iterator for Iter<'a, K, V, I> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, set_iter)) = &mut self.current {
+                if let Some(value) = set_iter.next() {
+                    return Some((key, value));
+                }
+            }
+
+            let (key, set) = self.map_iter.next()?;
+            self.current = Some((key, set.iter()));
+        }
+    }
+}
+
+pub struct Values<'a, K: 'a, V: 'a> {
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, value)| value)
+    }
+}
+
+// owned pairs, the key is cloned for every value but the last
+pub struct IntoIter<K, V> {
+    map_iter: btree_map::IntoIter<K, BTreeSet<V>>,
+    current: Option<(K, btree_set::IntoIter<V>)>,
+}
+
+impl<K: Clone, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, set_iter)) = &mut self.current {
+                if let Some(value) = set_iter.next() {
+                    if set_iter.len() == 0 {
+                        let (key, _) = self.current.take()?;
+                        return Some((key, value));
+                    }
+                    return Some((key.clone(), value));
+                }
+            }
+
+            let (key, set) = self.map_iter.next()?;
+            self.current = Some((key, set.into_iter()));
+        }
+    }
+}
+
+pub struct ValuesGroupIter<'a, V: 'a> {
+    iter: Option<btree_set::Iter<'a, V>>,
+}
+
+impl<'a, V: 'a> Iterator for ValuesGroupIter<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.as_mut()?.next()
+    }
+}
+
+pub struct GroupedValues<'a, K: 'a, V: 'a, I = btree_map::Iter<'a, K, BTreeSet<V>>> {
+    map_iter: I,
+    entries: PhantomData<(&'a K, &'a V)>,
+}
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item=(&'a K, &'a BTreeSet<V>)>> Iterator for GroupedValues<'a, K, V, I> {
+    type Item = (&'a K, ValuesGroupIter<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.map_iter.next().map(|(key, set)| (key, ValuesGroupIter { iter: Some(set.iter()) }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get() {
+        let mut map = OrderedMultiMap::new();
+
+        map.insert(11, 33);
+        map.insert(11, 22);
+        map.insert(11, 22);
+
+        assert_eq!(vec![&22, &33], map.get(&11).unwrap().collect::<Vec<_>>());
+        assert!(map.get(&22).is_none());
+    }
+
+    #[test]
+    fn test_iter_order() {
+        let map = OrderedMultiMap::from([
+            (33, 55),
+            (11, 33),
+            (22, 44),
+            (11, 22),
+        ]);
+
+        assert_eq!(
+            vec![(&11, &22), (&11, &33), (&22, &44), (&33, &55)],
+            map.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![&22, &33, &44, &55], map.values().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = OrderedMultiMap::from([
+            (11, 22),
+            (11, 33),
+            (22, 44),
+        ]);
+
+        map.remove(&11, &33);
+        map.remove(&22, &44);
+
+        assert_eq!(OrderedMultiMap::from([(11, 22)]), map);
+        assert!(!map.contains(&22, &44));
+    }
+
+    #[test]
+    fn test_grouped_values() {
+        let map = OrderedMultiMap::from([
+            (22, 44),
+            (11, 44),
+            (11, 22),
+            (33, 55),
+        ]);
+
+        let groups = map.grouped_values()
+            .map(|(key, values)| (*key, values.copied().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(11, vec![22, 44]), (22, vec![44]), (33, vec![55])], groups);
+    }
+
+    #[test]
+    fn test_default() {
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Id(u32);
+
+        let mut map = OrderedMultiMap::default();
+        map.insert(Id(1), Id(2));
+
+        assert!(map.contains(&Id(1), &Id(2)));
+    }
+
+    #[test]
+    fn test_range() {
+        let map = OrderedMultiMap::from([
+            (10, 1),
+            (20, 2),
+            (20, 3),
+            (30, 4),
+            (40, 5),
+        ]);
+
+        assert_eq!(vec![(&20, &2), (&20, &3), (&30, &4)], map.range(15..=30).collect::<Vec<_>>());
+        assert_eq!(vec![&40], map.grouped_range(35..).map(|(key, _)| key).collect::<Vec<_>>());
+        assert_eq!((Some(&10), Some(&40)), (map.first_key(), map.last_key()));
+    }
+
+    #[test]
+    fn test_lookups() {
+        let mut map = OrderedMultiMap::from([
+            ("b".to_string(), 2),
+            ("a".to_string(), 1),
+            ("a".to_string(), 3),
+        ]);
+
+        assert!(map.contains("a", &3));
+        assert_eq!(vec![&1, &3], map.get("a").unwrap().collect::<Vec<_>>());
+        assert_eq!(None, map.get_or_empty("c").next());
+        assert_eq!((3, 2), (map.len(), map.key_len()));
+        assert_eq!(vec!["a", "b"], map.keys().collect::<Vec<_>>());
+
+        map.remove("a", &1);
+        assert_eq!(Some(BTreeSet::from([2])), map.remove_key("b"));
+        map.retain(|_, value| *value != 3);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map = OrderedMultiMap::new();
+
+        let mut entry = map.entry(11);
+        assert!(entry.insert(22));
+        assert!(!entry.insert(22));
+        entry.extend([33, 44]);
+        assert!(entry.remove(&33));
+        assert_eq!(vec![&22, &44], entry.values().collect::<Vec<_>>());
+        assert_eq!(OrderedMultiMap::from([(11, 22), (11, 44)]), map);
+
+        let mut entry = map.entry(11);
+        entry.remove(&22);
+        entry.remove(&44);
+        assert!(entry.is_empty());
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_into_iter_drain() {
+        let mut map = OrderedMultiMap::from([
+            (22, 'c'),
+            (11, 'b'),
+            (11, 'a'),
+        ]);
+
+        assert_eq!(vec![(&11, &'a'), (&11, &'b'), (&22, &'c')], (&map).into_iter().collect::<Vec<_>>());
+        assert_eq!(vec![(11, 'a'), (11, 'b'), (22, 'c')], map.clone().into_iter().collect::<Vec<_>>());
+        assert_eq!(vec![(11, 'a'), (11, 'b'), (22, 'c')], map.drain().collect::<Vec<_>>());
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_text() {
+        let map = OrderedMultiMap::from([(61, 13), (47, 61), (47, 13), (47, 53)]);
+
+        assert_eq!("47|13\n47|53\n47|61\n61|13\n", map.text(TextFormat::Pairs("|")));
+        assert_eq!("47: 13 53 61\n61: 13\n", map.to_string());
+        assert_eq!(Ok(map.clone()), OrderedMultiMap::parse_text(&map.text(TextFormat::Pairs("|")), TextFormat::Pairs("|")));
+        assert_eq!(Ok(map.clone()), map.to_string().parse());
+
+        let error = "47: 53\n97: 1x".parse::<OrderedMultiMap<u8, u8>>().unwrap_err();
+        assert_eq!((2, 5), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_freeze() {
+        let map = OrderedMultiMap::from([(33, 55), (11, 33), (11, 22)]);
+        let pairs = map.iter().map(|(key, value)| (*key, *value)).collect::<Vec<_>>();
+
+        let frozen = map.freeze();
+
+        assert_eq!(pairs, frozen.iter().map(|(key, value)| (*key, *value)).collect::<Vec<_>>());
+        assert!(frozen.contains(&11, &33));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let left = OrderedMultiMap::from([(1, 'a'), (1, 'b'), (2, 'c')]);
+        let right = OrderedMultiMap::from([(1, 'b'), (3, 'd')]);
+
+        assert_eq!(OrderedMultiMap::from([(1, 'a'), (1, 'b'), (2, 'c'), (3, 'd')]), left.union(&right));
+        assert_eq!(OrderedMultiMap::from([(1, 'b')]), left.intersection(&right));
+        assert_eq!(OrderedMultiMap::from([(1, 'a'), (2, 'c')]), left.difference(&right));
+        assert_eq!(OrderedMultiMap::from([(1, 'a'), (2, 'c'), (3, 'd')]), left.symmetric_difference(&right));
+        assert!(left.intersection(&right).is_subset(&right));
+        assert!(!left.is_subset(&right));
+        assert_eq!(OrderedMultiMap::from([('a', 1), ('b', 1), ('c', 2)]), left.invert());
+    }
+}
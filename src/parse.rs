@@ -0,0 +1,310 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+// a line of the input together with its 1-based number; after a split, `text` is only a part of
+// the line and `offset` counts the characters before it, so errors still point into the whole line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Self {
+            number,
+            text,
+            offset: 0,
+        }
+    }
+
+    // `column` is 1-based and counted within `text`
+    pub fn error(&self, column: usize, message: String) -> ParseError {
+        ParseError {
+            line: self.number,
+            column: self.offset + column,
+            message,
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError> where T::Err: Display {
+        self.parse_part(self.text.trim())
+    }
+
+    // all integers in the line, a `-` directly before a digit makes it negative
+    pub fn integers<T: FromStr>(&self) -> Result<Vec<T>, ParseError> where T::Err: Display {
+        let bytes = self.text.as_bytes();
+        let mut result = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let is_negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+            if !is_negative && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+
+            result.push(self.parse_part(&self.text[start..i])?);
+        }
+
+        Ok(result)
+    }
+
+    pub fn list<T: FromStr>(&self, delimiter: &str) -> Result<Vec<T>, ParseError> where T::Err: Display {
+        self.text.split(delimiter).map(|item| self.parse_part(item.trim())).collect()
+    }
+
+    // whitespace separated values
+    pub fn fields<T: FromStr>(&self) -> Result<Vec<T>, ParseError> where T::Err: Display {
+        self.text.split_whitespace().map(|item| self.parse_part(item)).collect()
+    }
+
+    pub fn key_value<K: FromStr, V: FromStr>(&self, separator: &str) -> Result<(K, V), ParseError>
+    where
+        K::Err: Display,
+        V::Err: Display,
+    {
+        let (key, value) = self.split_once(separator)?;
+        Ok((key.parse()?, value.parse()?))
+    }
+
+    pub fn split_once(&self, separator: &str) -> Result<(Line<'a>, Line<'a>), ParseError> {
+        let Some((key, value)) = self.text.split_once(separator) else {
+            return Err(self.error(1, format!("expected `{separator}` in `{}`", self.text)));
+        };
+
+        let value_offset = self.offset + key.chars().count() + separator.chars().count();
+        Ok((
+            Line { number: self.number, text: key, offset: self.offset },
+            Line { number: self.number, text: value, offset: value_offset },
+        ))
+    }
+
+    fn parse_part<T: FromStr>(&self, part: &str) -> Result<T, ParseError> where T::Err: Display {
+        part.parse().map_err(|err| self.error(self.column_of(part), format!("can't parse `{part}`: {err}")))
+    }
+
+    fn column_of(&self, part: &str) -> usize {
+        let offset = (part.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+        self.text[..offset.min(self.text.len())].chars().count() + 1
+    }
+}
+
+// unix line endings, no UTF-8 BOM and no trailing whitespace on any line
+pub fn normalize(input: &str) -> String {
+    const BOM: char = '\u{feff}';
+
+    let input = input.strip_prefix(BOM).unwrap_or(input);
+    let unix_input = input.replace("\r\n", "\n").replace('\r', "\n");
+
+    let mut result = unix_input.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+    if unix_input.ends_with('\n') {
+        result.push('\n');
+    }
+
+    result
+}
+
+pub fn non_ascii_warnings(input: &str) -> Vec<ParseError> {
+    lines(input)
+        .flat_map(|line| {
+            line.text.chars().enumerate()
+                .filter(|(_, char)| !char.is_ascii())
+                .map(move |(column, char)| line.error(column + 1, format!("non-ASCII character {char:?}")))
+        })
+        .collect()
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item=Line<'_>> {
+    (1..).zip(input.lines())
+        .map(|(number, text)| Line::new(number, text))
+        .filter(|line| !line.text.trim().is_empty())
+}
+
+// groups of non-blank lines separated by blank lines
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = Vec::new();
+    let mut current = Vec::new();
+
+    for (number, text) in (1..).zip(input.lines()) {
+        if text.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(current);
+                current = Vec::new();
+            }
+        } else {
+            current.push(Line::new(number, text));
+        }
+    }
+
+    if !current.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+pub fn integers<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> where T::Err: Display {
+    let mut result = Vec::new();
+    for line in lines(input) {
+        result.extend(line.integers()?);
+    }
+
+    Ok(result)
+}
+
+pub fn char_grid(input: &str) -> Result<Grid<char>, ParseError> {
+    let lines = lines(input).collect::<Vec<_>>();
+    let width = lines.first().map_or(0, |line| line.text.chars().count());
+
+    if let Some(line) = lines.iter().find(|line| line.text.chars().count() != width) {
+        let message = format!("expected {width} columns, found {}", line.text.chars().count());
+        return Err(line.error(1, message));
+    }
+
+    let data = lines.iter().flat_map(|line| line.text.chars()).collect();
+    Ok(Grid::try_new(width, lines.len(), data).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::GridView;
+
+    #[test]
+    fn test_lines_numbers() {
+        let input = "\na\n\nb";
+
+        let actual = lines(input).map(|line| (line.number, line.text)).collect::<Vec<_>>();
+
+        assert_eq!(vec![(2, "a"), (4, "b")], actual);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "1|2\n3|4\n\n1,2\n\n\n5";
+
+        let sections = sections(input);
+
+        assert_eq!(3, sections.len());
+        assert_eq!(vec!["1|2", "3|4"], sections[0].iter().map(|line| line.text).collect::<Vec<_>>());
+        assert_eq!(4, sections[1][0].number);
+        assert_eq!(7, sections[2][0].number);
+    }
+
+    #[test]
+    fn test_integers() {
+        let line = Line::new(1, "p=0,4 v=3,-3 x-2");
+
+        assert_eq!(vec![0, 4, 3, -3, -2], line.integers::<i32>().unwrap());
+        assert_eq!(vec![1, 22, 333], integers::<u32>("a1b22\n\nc333").unwrap());
+    }
+
+    #[test]
+    fn test_integers_error() {
+        let line = Line::new(3, "v=3,-3");
+
+        let error = line.integers::<u32>().unwrap_err();
+
+        assert_eq!((3, 5), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_list_and_fields() {
+        let line = Line::new(1, "75,47,61");
+
+        assert_eq!(vec![75, 47, 61], line.list::<u8>(",").unwrap());
+        assert_eq!(vec![3, 4], Line::new(1, "3   4").fields::<u32>().unwrap());
+    }
+
+    #[test]
+    fn test_list_error_column() {
+        let line = Line::new(2, "75,4x,61");
+
+        let error = line.list::<u8>(",").unwrap_err();
+
+        assert_eq!((2, 4), (error.line, error.column));
+        assert_eq!("line 2, column 4: can't parse `4x`: invalid digit found in string", error.to_string());
+    }
+
+    #[test]
+    fn test_key_value() {
+        let line = Line::new(1, "47|53");
+
+        assert_eq!((47, 53), line.key_value::<u8, u8>("|").unwrap());
+        assert!(line.key_value::<u8, u8>(":").is_err());
+    }
+
+    #[test]
+    fn test_error_column_after_split() {
+        let error = Line::new(1, "47|5x").key_value::<u8, u8>("|").unwrap_err();
+        assert_eq!((1, 4), (error.line, error.column));
+
+        let (_, values) = Line::new(2, "190: 10 x9").split_once(":").unwrap();
+        let error = values.fields::<u32>().unwrap_err();
+        assert_eq!((2, 9), (error.line, error.column));
+
+        let error = Line::new(3, "ab: cd").split_once("|").unwrap_err();
+        assert_eq!((3, 1), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_lines_skip_blank() {
+        let input = "a\n  \nb\n\t\n\nc";
+
+        assert_eq!(vec![1, 3, 6], lines(input).map(|line| line.number).collect::<Vec<_>>());
+        assert_eq!(3, sections(input).len());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let input = "\u{feff}MSX  \r\nASM\t\r\n\r\nXXM\r\n";
+
+        assert_eq!("MSX\nASM\n\nXXM\n", normalize(input));
+        assert_eq!("a\nb", normalize("a\rb"));
+        assert_eq!("2333133121414131402\n", normalize("2333133121414131402\r\n"));
+    }
+
+    #[test]
+    fn test_non_ascii_warnings() {
+        let warnings = non_ascii_warnings("ab\n\na\u{2014}b");
+
+        assert_eq!(1, warnings.len());
+        assert_eq!((3, 2), (warnings[0].line, warnings[0].column));
+    }
+
+    #[test]
+    fn test_char_grid() {
+        let grid = char_grid("\nab\ncd\n").unwrap();
+
+        assert_eq!((2, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&'c'), grid.get(0, 1));
+
+        let error = char_grid("ab\nc").unwrap_err();
+        assert_eq!(2, error.line);
+    }
+}
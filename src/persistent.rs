@@ -0,0 +1,543 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+// immutable AVL tree; insert and remove copy only the O(log n) nodes on the path and share the rest,
+// so old versions stay valid and backtracking is just keeping the previous version around
+pub struct PersistentMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+type Link<K, V> = Option<Rc<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    height: u8,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K: Ord + Clone, V: Clone> PersistentMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    // new version with `key` set to `value`
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let (root, added) = insert_node(&self.root, key, value);
+        Self {
+            root: Some(root),
+            len: self.len + usize::from(added),
+        }
+    }
+
+    // new version without `key`, a missing key gives a version sharing everything
+    pub fn remove<Q: ?Sized + Ord>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+    {
+        match remove_node(&self.root, key) {
+            Some(root) => Self {
+                root,
+                len: self.len - 1,
+            },
+            None => self.clone(),
+        }
+    }
+
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            match key.cmp(node.key.borrow()) {
+                Ordering::Less => link = &node.left,
+                Ordering::Greater => link = &node.right,
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+
+        None
+    }
+
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get(key).is_some()
+    }
+
+    // in key order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+        };
+        iter.push_left_spine(&self.root);
+        iter
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item=&K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    fn height(&self) -> u8 {
+        height(&self.root)
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> u8 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn make_node<K, V>(key: K, value: V, left: Link<K, V>, right: Link<K, V>) -> Rc<Node<K, V>> {
+    Rc::new(Node {
+        height: height(&left).max(height(&right)) + 1,
+        key,
+        value,
+        left,
+        right,
+    })
+}
+
+// builds a node whose subtrees differ in height by at most 2, rotating it back into AVL shape
+fn balance<K: Clone, V: Clone>(key: K, value: V, left: Link<K, V>, right: Link<K, V>) -> Rc<Node<K, V>> {
+    let (left_height, right_height) = (height(&left), height(&right));
+
+    if left_height > right_height + 1 {
+        let left = left.unwrap();
+        if height(&left.left) >= height(&left.right) {
+            let new_right = make_node(key, value, left.right.clone(), right);
+            return make_node(left.key.clone(), left.value.clone(), left.left.clone(), Some(new_right));
+        }
+
+        let middle = left.right.as_ref().unwrap();
+        let new_left = make_node(left.key.clone(), left.value.clone(), left.left.clone(), middle.left.clone());
+        let new_right = make_node(key, value, middle.right.clone(), right);
+        return make_node(middle.key.clone(), middle.value.clone(), Some(new_left), Some(new_right));
+    }
+
+    if right_height > left_height + 1 {
+        let right = right.unwrap();
+        if height(&right.right) >= height(&right.left) {
+            let new_left = make_node(key, value, left, right.left.clone());
+            return make_node(right.key.clone(), right.value.clone(), Some(new_left), right.right.clone());
+        }
+
+        let middle = right.left.as_ref().unwrap();
+        let new_left = make_node(key, value, left, middle.left.clone());
+        let new_right = make_node(right.key.clone(), right.value.clone(), middle.right.clone(), right.right.clone());
+        return make_node(middle.key.clone(), middle.value.clone(), Some(new_left), Some(new_right));
+    }
+
+    make_node(key, value, left, right)
+}
+
+// returns the new subtree and whether the key was not there before
+fn insert_node<K: Ord + Clone, V: Clone>(link: &Link<K, V>, key: K, value: V) -> (Rc<Node<K, V>>, bool) {
+    let Some(node) = link else {
+        return (make_node(key, value, None, None), true);
+    };
+
+    match key.cmp(&node.key) {
+        Ordering::Less => {
+            let (left, added) = insert_node(&node.left, key, value);
+            (balance(node.key.clone(), node.value.clone(), Some(left), node.right.clone()), added)
+        }
+        Ordering::Greater => {
+            let (right, added) = insert_node(&node.right, key, value);
+            (balance(node.key.clone(), node.value.clone(), node.left.clone(), Some(right)), added)
+        }
+        Ordering::Equal => (make_node(key, value, node.left.clone(), node.right.clone()), false),
+    }
+}
+
+// `None` if the key is missing, otherwise the new subtree
+fn remove_node<K, V, Q>(link: &Link<K, V>, key: &Q) -> Option<Link<K, V>>
+where
+    K: Ord + Clone + Borrow<Q>,
+    V: Clone,
+    Q: ?Sized + Ord,
+{
+    let node = link.as_ref()?;
+
+    let subtree = match key.cmp(node.key.borrow()) {
+        Ordering::Less => {
+            let left = remove_node(&node.left, key)?;
+            balance(node.key.clone(), node.value.clone(), left, node.right.clone())
+        }
+        Ordering::Greater => {
+            let right = remove_node(&node.right, key)?;
+            balance(node.key.clone(), node.value.clone(), node.left.clone(), right)
+        }
+        Ordering::Equal => match (&node.left, &node.right) {
+            (None, right) => return Some(right.clone()),
+            (left, None) => return Some(left.clone()),
+            (left, Some(right)) => {
+                let (key, value, right) = remove_min(right);
+                balance(key, value, left.clone(), right)
+            }
+        },
+    };
+
+    Some(Some(subtree))
+}
+
+fn remove_min<K: Clone, V: Clone>(node: &Rc<Node<K, V>>) -> (K, V, Link<K, V>) {
+    match &node.left {
+        None => (node.key.clone(), node.value.clone(), node.right.clone()),
+        Some(left) => {
+            let (key, value, left) = remove_min(left);
+            (key, value, Some(balance(node.key.clone(), node.value.clone(), left, node.right.clone())))
+        }
+    }
+}
+
+// cloning a version is O(1)
+impl<K, V> Clone for PersistentMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Default for PersistentMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V: Clone + PartialEq> PartialEq for PersistentMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Ord + Clone, V: Clone + Eq> Eq for PersistentMap<K, V> {}
+
+impl<K: Ord + Clone + Debug, V: Clone + Debug> Debug for PersistentMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> FromIterator<(K, V)> for PersistentMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::new(), |map, (key, value)| map.insert(key, value))
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left_spine(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(&node.right);
+        Some((&node.key, &node.value))
+    }
+}
+
+pub struct PersistentSet<T> {
+    map: PersistentMap<T, ()>,
+}
+
+impl<T: Ord + Clone> PersistentSet<T> {
+    pub fn new() -> Self {
+        Self {
+            map: PersistentMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn insert(&self, value: T) -> Self {
+        Self {
+            map: self.map.insert(value, ()),
+        }
+    }
+
+    pub fn remove<Q: ?Sized + Ord>(&self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+    {
+        Self {
+            map: self.map.remove(value),
+        }
+    }
+
+    pub fn contains<Q: ?Sized + Ord>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&T> {
+        self.map.keys()
+    }
+}
+
+impl<T> Clone for PersistentSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T: Ord + Clone> Default for PersistentSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> PartialEq for PersistentSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Ord + Clone> Eq for PersistentSet<T> {}
+
+impl<T: Ord + Clone + Debug> Debug for PersistentSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for PersistentSet<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), |set, value| set.insert(value))
+    }
+}
+
+// MultiMap on top of the persistent tree, every update returns a new version
+pub struct PersistentMultiMap<K, V> {
+    map: PersistentMap<K, PersistentSet<V>>,
+    len: usize,
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> PersistentMultiMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            map: PersistentMap::new(),
+            len: 0,
+        }
+    }
+
+    // number of (key, value) pairs
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn key_len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let values = self.map.get(&key).cloned().unwrap_or_default();
+        if values.contains(&value) {
+            return self.clone();
+        }
+
+        Self {
+            map: self.map.insert(key, values.insert(value)),
+            len: self.len + 1,
+        }
+    }
+
+    pub fn remove(&self, key: &K, value: &V) -> Self {
+        let Some(values) = self.map.get(key).filter(|values| values.contains(value)) else {
+            return self.clone();
+        };
+
+        let values = values.remove(value);
+        let map = if values.is_empty() {
+            self.map.remove(key)
+        } else {
+            self.map.insert(key.clone(), values)
+        };
+
+        Self {
+            map,
+            len: self.len - 1,
+        }
+    }
+
+    pub fn remove_key(&self, key: &K) -> Self {
+        let Some(values) = self.map.get(key) else {
+            return self.clone();
+        };
+
+        Self {
+            map: self.map.remove(key),
+            len: self.len - values.len(),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&PersistentSet<V>> {
+        self.map.get(key)
+    }
+
+    pub fn contains(&self, key: &K, value: &V) -> bool {
+        self.map.get(key).is_some_and(|values| values.contains(value))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item=&K> {
+        self.map.keys()
+    }
+
+    // in key and then value order
+    pub fn iter(&self) -> impl Iterator<Item=(&K, &V)> {
+        self.map.iter().flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+
+    pub fn grouped_values(&self) -> impl Iterator<Item=(&K, &PersistentSet<V>)> {
+        self.map.iter()
+    }
+}
+
+impl<K, V> Clone for PersistentMultiMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> Default for PersistentMultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> PartialEq for PersistentMultiMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> Eq for PersistentMultiMap<K, V> {}
+
+impl<K: Ord + Clone + Debug, V: Ord + Clone + Debug> Debug for PersistentMultiMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.grouped_values()).finish()
+    }
+}
+
+impl<K: Ord + Clone, V: Ord + Clone, const N: usize> From<[(K, V); N]> for PersistentMultiMap<K, V> {
+    fn from(keys_values: [(K, V); N]) -> Self {
+        keys_values.into_iter().collect()
+    }
+}
+
+impl<K: Ord + Clone, V: Ord + Clone> FromIterator<(K, V)> for PersistentMultiMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::new(), |map, (key, value)| map.insert(key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_independent() {
+        let empty = PersistentMap::new();
+        let one = empty.insert(1, 'a');
+        let two = one.insert(2, 'b');
+        let replaced = two.insert(1, 'c');
+        let removed = two.remove(&1);
+
+        assert!(empty.is_empty());
+        assert_eq!(vec![(&1, &'a')], one.iter().collect::<Vec<_>>());
+        assert_eq!(vec![(&1, &'a'), (&2, &'b')], two.iter().collect::<Vec<_>>());
+        assert_eq!(Some(&'c'), replaced.get(&1));
+        assert_eq!(2, replaced.len());
+        assert_eq!(vec![(&2, &'b')], removed.iter().collect::<Vec<_>>());
+        assert_eq!(two, removed.insert(1, 'a'));
+    }
+
+    #[test]
+    fn test_stays_balanced() {
+        let mut map = (0..1024).map(|i| (i, i * 2)).collect::<PersistentMap<_, _>>();
+
+        assert_eq!(1024, map.len());
+        assert!(map.height() <= 14);
+        assert_eq!(Some(&200), map.get(&100));
+
+        for i in (0..1024).step_by(2) {
+            map = map.remove(&i);
+        }
+
+        assert_eq!(512, map.len());
+        assert!(map.height() <= 13);
+        assert_eq!((1..1024).step_by(2).collect::<Vec<_>>(), map.keys().copied().collect::<Vec<_>>());
+        assert_eq!(512, map.remove(&4000).len());
+    }
+
+    #[test]
+    fn test_set() {
+        let set = PersistentSet::from_iter(["b", "a", "c"]);
+        let smaller = set.remove("b");
+
+        assert!(set.contains("b"));
+        assert!(!smaller.contains("b"));
+        assert_eq!(vec![&"a", &"c"], smaller.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_multimap_backtracking() {
+        let rules = PersistentMultiMap::from([(47, 53), (47, 13), (97, 13)]);
+
+        // try a branch, then drop it and carry on from the original version
+        let branch = rules.remove(&47, &53).remove_key(&97).insert(61, 13);
+
+        assert_eq!(vec![(&47, &13), (&61, &13)], branch.iter().collect::<Vec<_>>());
+        assert_eq!(2, branch.len());
+        assert_eq!(3, rules.len());
+        assert!(rules.contains(&47, &53));
+        assert_eq!(rules, rules.insert(47, 53));
+        assert_eq!(vec![&13, &53], rules.get(&47).unwrap().iter().collect::<Vec<_>>());
+    }
+}
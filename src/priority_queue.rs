@@ -0,0 +1,322 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+// binary min-heap that knows where every key sits, so priorities can be lowered in place
+#[derive(Debug, Clone)]
+pub struct IndexedMinHeap<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Eq + Hash + Clone, P: Ord> IndexedMinHeap<K, P> {
+    pub fn new() -> Self {
+        Self {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|position| &self.heap[*position].1)
+    }
+
+    pub fn peek_min(&self) -> Option<(&K, &P)> {
+        self.heap.first().map(|(key, priority)| (key, priority))
+    }
+
+    // inserts the key or lowers its priority; returns false if the stored priority was already lower or equal
+    pub fn push_or_decrease(&mut self, key: K, priority: P) -> bool {
+        match self.positions.get(&key) {
+            Some(&position) if self.heap[position].1 <= priority => false,
+            Some(&position) => {
+                self.heap[position].1 = priority;
+                self.sift_up(position);
+                true
+            }
+            None => {
+                self.heap.push((key.clone(), priority));
+                self.positions.insert(key, self.heap.len() - 1);
+                self.sift_up(self.heap.len() - 1);
+                true
+            }
+        }
+    }
+
+    pub fn pop_min(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (key, priority) = self.heap.pop()?;
+        self.positions.remove(&key);
+        self.sift_down(0);
+
+        Some((key, priority))
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.positions.clear();
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.heap[parent].1 <= self.heap[position].1 {
+                break;
+            }
+            self.swap(parent, position);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let left = position * 2 + 1;
+            let right = left + 1;
+            let mut smallest = position;
+
+            if left < self.heap.len() && self.heap[left].1 < self.heap[smallest].1 {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.heap[right].1 < self.heap[smallest].1 {
+                smallest = right;
+            }
+            if smallest == position {
+                break;
+            }
+
+            self.swap(position, smallest);
+            position = smallest;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        if let Some(position) = self.heap.get(a).and_then(|(key, _)| self.positions.get_mut(key)) {
+            *position = a;
+        }
+        if let Some(position) = self.heap.get(b).and_then(|(key, _)| self.positions.get_mut(key)) {
+            *position = b;
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, P: Ord> Default for IndexedMinHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Dial's queue for Dijkstra with integer edge weights up to `max_weight`: the pending priorities
+// always span at most `max_weight + 1` values, so a ring of that many buckets is enough;
+// items with equal priority come out in insertion order
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    buckets: Vec<VecDeque<T>>,
+    lowest: usize,
+    highest: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new(max_weight: usize) -> Self {
+        Self {
+            buckets: (0..=max_weight).map(|_| VecDeque::new()).collect(),
+            lowest: 0,
+            highest: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // panics if the pending priorities would span more than `max_weight + 1` values
+    pub fn push(&mut self, priority: usize, item: T) {
+        if self.len == 0 {
+            (self.lowest, self.highest) = (priority, priority);
+        } else {
+            self.lowest = self.lowest.min(priority);
+            self.highest = self.highest.max(priority);
+        }
+        assert!(
+            self.highest - self.lowest < self.buckets.len(),
+            "priority {priority} is more than {} away from the other pending priorities",
+            self.buckets.len() - 1
+        );
+
+        let index = priority % self.buckets.len();
+        self.buckets[index].push_back(item);
+        self.len += 1;
+    }
+
+    pub fn pop_min(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let priority = self.lowest;
+        let index = priority % self.buckets.len();
+        let item = self.buckets[index].pop_front()?;
+        self.len -= 1;
+
+        // keep `lowest` at the smallest pending priority, so the window only covers what is queued
+        while self.len > 0 && self.buckets[self.lowest % self.buckets.len()].is_empty() {
+            self.lowest += 1;
+        }
+
+        Some((priority, item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heap_order() {
+        let mut heap = IndexedMinHeap::new();
+
+        for (key, priority) in [('a', 5), ('b', 3), ('c', 8), ('d', 1), ('e', 4)] {
+            heap.push_or_decrease(key, priority);
+        }
+
+        let order = std::iter::from_fn(|| heap.pop_min()).collect::<Vec<_>>();
+
+        assert_eq!(vec![('d', 1), ('b', 3), ('e', 4), ('a', 5), ('c', 8)], order);
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = IndexedMinHeap::new();
+        heap.push_or_decrease("far", 10);
+        heap.push_or_decrease("near", 5);
+
+        assert!(heap.push_or_decrease("far", 2));
+        assert!(!heap.push_or_decrease("near", 7));
+
+        assert!(heap.contains(&"far"));
+        assert_eq!(Some(&5), heap.priority(&"near"));
+        assert_eq!(2, heap.len());
+        assert_eq!(Some(("far", 2)), heap.pop_min());
+        assert!(!heap.contains(&"far"));
+        assert_eq!(Some(("near", 5)), heap.pop_min());
+        assert_eq!(None, heap.pop_min());
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let edges = [(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1), (2, 3, 5)];
+        let mut distances = HashMap::new();
+        let mut heap = IndexedMinHeap::new();
+        heap.push_or_decrease(0, 0);
+
+        while let Some((node, distance)) = heap.pop_min() {
+            distances.insert(node, distance);
+            for (_, to, weight) in edges.iter().filter(|(from, _, _)| *from == node) {
+                if !distances.contains_key(to) {
+                    heap.push_or_decrease(*to, distance + weight);
+                }
+            }
+        }
+
+        assert_eq!(Some(&3), distances.get(&1));
+        assert_eq!(Some(&4), distances.get(&3));
+    }
+
+    #[test]
+    fn test_bucket_queue() {
+        let mut queue = BucketQueue::new(3);
+
+        queue.push(3, 'a');
+        queue.push(1, 'b');
+        queue.push(3, 'c');
+        queue.push(0, 'd');
+
+        assert_eq!(4, queue.len());
+        assert_eq!(Some((0, 'd')), queue.pop_min());
+        assert_eq!(Some((1, 'b')), queue.pop_min());
+        queue.push(2, 'e');
+        assert_eq!(Some((2, 'e')), queue.pop_min());
+        assert_eq!(Some((3, 'a')), queue.pop_min());
+        assert_eq!(Some((3, 'c')), queue.pop_min());
+        assert_eq!(None, queue.pop_min());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_bucket_queue_wraps_around() {
+        // a chain 0 -> 1 -> ... -> 999 with weights 1..=3 and a few shortcuts of weight 3
+        let weight = |node: usize| node % 3 + 1;
+        let mut distances = vec![usize::MAX; 1000];
+        let mut queue = BucketQueue::new(3);
+        distances[0] = 0;
+        queue.push(0, 0);
+
+        while let Some((distance, node)) = queue.pop_min() {
+            if distance > distances[node] {
+                continue;
+            }
+
+            let mut edges = vec![(node + 1, weight(node))];
+            if node % 10 == 0 {
+                edges.push((node + 2, 3));
+            }
+            for (next, weight) in edges.into_iter().filter(|(next, _)| *next < 1000) {
+                if distance + weight < distances[next] {
+                    distances[next] = distance + weight;
+                    queue.push(distance + weight, next);
+                }
+            }
+        }
+
+        assert_eq!(4, queue.buckets.len());
+        assert_eq!(3, distances[2]);
+        assert_eq!(distances[998] + weight(998), distances[999]);
+        assert!(distances[999] > 1000);
+    }
+
+    #[test]
+    fn test_bucket_queue_window_follows_pops() {
+        let mut queue = BucketQueue::new(3);
+
+        queue.push(0, 'a');
+        queue.push(3, 'b');
+        assert_eq!(Some((0, 'a')), queue.pop_min());
+        queue.push(4, 'c');
+        queue.push(6, 'd');
+
+        assert_eq!(Some((3, 'b')), queue.pop_min());
+        assert_eq!(Some((4, 'c')), queue.pop_min());
+        assert_eq!(Some((6, 'd')), queue.pop_min());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bucket_queue_window() {
+        let mut queue = BucketQueue::new(3);
+
+        queue.push(10, 'a');
+        queue.push(14, 'b');
+    }
+}
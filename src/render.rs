@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use crate::grid::GridView;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+        }
+    }
+
+    fn paint(color: Option<Color>, glyph: char, output: &mut String) {
+        match color {
+            Some(color) => write!(output, "\x1b[{}m{}\x1b[0m", color.ansi_code(), glyph).unwrap(),
+            None => output.push(glyph),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    name: String,
+    glyph: char,
+    color: Option<Color>,
+    cells: HashSet<(usize, usize)>,
+}
+
+impl Layer {
+    pub fn new(name: &str, glyph: char) -> Self {
+        Self {
+            name: name.to_string(),
+            glyph,
+            color: None,
+            cells: HashSet::new(),
+        }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_cells<I: IntoIterator<Item=(usize, usize)>>(mut self, cells: I) -> Self {
+        self.cells.extend(cells);
+        self
+    }
+
+    pub fn insert(&mut self, x: usize, y: usize) {
+        self.cells.insert((x, y));
+    }
+}
+
+// draws a base grid with overlay layers on top of it, later layers win
+pub struct Renderer<'a, G: GridView + ?Sized, F: Fn(&G::Cell) -> char> {
+    base: &'a G,
+    glyph: F,
+    layers: Vec<Layer>,
+    legend: bool,
+}
+
+impl<'a, G: GridView + ?Sized, F: Fn(&G::Cell) -> char> Renderer<'a, G, F> {
+    pub fn new(base: &'a G, glyph: F) -> Self {
+        Self {
+            base,
+            glyph,
+            layers: Vec::new(),
+            legend: false,
+        }
+    }
+
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn with_legend(mut self) -> Self {
+        self.legend = true;
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        for y in 0..self.base.height() {
+            if y > 0 {
+                output.push('\n');
+            }
+
+            for x in 0..self.base.width() {
+                match self.layers.iter().rev().find(|layer| layer.cells.contains(&(x, y))) {
+                    Some(layer) => Color::paint(layer.color, layer.glyph, &mut output),
+                    None => output.push((self.glyph)(self.base.cell(x, y))),
+                }
+            }
+        }
+
+        if self.legend {
+            for layer in &self.layers {
+                output.push('\n');
+                Color::paint(layer.color, layer.glyph, &mut output);
+                write!(output, " {}", layer.name).unwrap();
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn sample() -> Grid<char> {
+        Grid::try_from_lines(&[
+            "...",
+            ".#.",
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_render_base() {
+        let grid = sample();
+
+        assert_eq!("...\n.#.", Renderer::new(&grid, |c| *c).render());
+    }
+
+    #[test]
+    fn test_render_layers_order() {
+        let grid = sample();
+
+        let output = Renderer::new(&grid, |c| *c)
+            .layer(Layer::new("path", 'X').with_cells([(0, 0), (1, 0)]))
+            .layer(Layer::new("start", '^').with_cells([(0, 0)]))
+            .render();
+
+        assert_eq!("^X.\n.#.", output);
+    }
+
+    #[test]
+    fn test_render_color_and_legend() {
+        let grid = sample();
+
+        let output = Renderer::new(&grid, |c| *c)
+            .layer(Layer::new("antinode", '*').with_color(Color::Red).with_cells([(2, 1)]))
+            .with_legend()
+            .render();
+
+        assert_eq!("...\n.#\x1b[31m*\x1b[0m\n\x1b[31m*\x1b[0m antinode", output);
+    }
+}
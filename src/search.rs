@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use crate::priority_queue::IndexedMinHeap;
+
+// state-space search over a successor function; every method returns the path from start to goal, both included
+pub struct Search<S, I, F> {
+    successors: F,
+    depth_limit: Option<usize>,
+    states: PhantomData<fn(&S) -> I>,
+}
+
+impl<S, I, F> Search<S, I, F>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item=S>,
+    F: Fn(&S) -> I,
+{
+    pub fn new(successors: F) -> Self {
+        Self {
+            successors,
+            depth_limit: None,
+            states: PhantomData,
+        }
+    }
+
+    // maximum number of steps from the start
+    pub fn with_depth_limit(mut self, depth_limit: usize) -> Self {
+        self.depth_limit = Some(depth_limit);
+        self
+    }
+
+    // shortest path in steps
+    pub fn bfs<G: Fn(&S) -> bool>(&self, start: S, is_goal: G) -> Option<Vec<S>> {
+        let mut parents = HashMap::from([(start.clone(), None)]);
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some((state, depth)) = queue.pop_front() {
+            if is_goal(&state) {
+                return Some(build_path(&parents, state));
+            }
+            if self.depth_limit.is_some_and(|limit| depth >= limit) {
+                continue;
+            }
+
+            for next in (self.successors)(&state) {
+                if !parents.contains_key(&next) {
+                    parents.insert(next.clone(), Some(state.clone()));
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    // without a depth limit every state is visited once; with one, only the current path is
+    // excluded, so states reachable by several paths within the limit are not missed
+    pub fn dfs<G: Fn(&S) -> bool>(&self, start: S, is_goal: G) -> Option<Vec<S>> {
+        self.depth_first(start, &is_goal, self.depth_limit).0
+    }
+
+    // depth-limited DFS with limits 0, 1, ... up to the depth limit, finds a shortest path;
+    // stops early once a pass explores everything without being cut off by its limit
+    pub fn iterative_deepening<G: Fn(&S) -> bool>(&self, start: S, is_goal: G) -> Option<Vec<S>> {
+        let max_depth = self.depth_limit.unwrap_or(usize::MAX);
+
+        for limit in 0..=max_depth {
+            match self.depth_first(start.clone(), &is_goal, Some(limit)) {
+                (Some(path), _) => return Some(path),
+                (None, false) => return None,
+                (None, true) => {}
+            }
+        }
+
+        None
+    }
+
+    // BFS that keeps only the `width` lowest scored states of every layer
+    pub fn beam<G, C, H>(&self, start: S, is_goal: G, width: usize, score: H) -> Option<Vec<S>>
+    where
+        G: Fn(&S) -> bool,
+        C: Ord,
+        H: Fn(&S) -> C,
+    {
+        let mut parents = HashMap::from([(start.clone(), None)]);
+        let mut layer = vec![start];
+        let mut depth = 0;
+
+        while !layer.is_empty() {
+            if let Some(goal) = layer.iter().find(|state| is_goal(state)) {
+                return Some(build_path(&parents, goal.clone()));
+            }
+            if self.depth_limit.is_some_and(|limit| depth >= limit) {
+                break;
+            }
+
+            let mut next_layer = Vec::new();
+            for state in &layer {
+                for next in (self.successors)(state) {
+                    if !parents.contains_key(&next) {
+                        parents.insert(next.clone(), Some(state.clone()));
+                        next_layer.push(next);
+                    }
+                }
+            }
+
+            next_layer.sort_by_cached_key(|state| score(state));
+            next_layer.truncate(width);
+            layer = next_layer;
+            depth += 1;
+        }
+
+        None
+    }
+
+    // greedy best-first: always expands the open state with the lowest heuristic
+    pub fn best_first<G, C, H>(&self, start: S, is_goal: G, heuristic: H) -> Option<Vec<S>>
+    where
+        G: Fn(&S) -> bool,
+        C: Ord,
+        H: Fn(&S) -> C,
+    {
+        let mut parents = HashMap::from([(start.clone(), None)]);
+        let mut depths = HashMap::from([(start.clone(), 0)]);
+        let mut open = IndexedMinHeap::new();
+        open.push_or_decrease(start.clone(), heuristic(&start));
+
+        while let Some((state, _)) = open.pop_min() {
+            if is_goal(&state) {
+                return Some(build_path(&parents, state));
+            }
+
+            let depth = depths[&state];
+            if self.depth_limit.is_some_and(|limit| depth >= limit) {
+                continue;
+            }
+
+            for next in (self.successors)(&state) {
+                if !parents.contains_key(&next) {
+                    parents.insert(next.clone(), Some(state.clone()));
+                    depths.insert(next.clone(), depth + 1);
+                    let priority = heuristic(&next);
+                    open.push_or_decrease(next, priority);
+                }
+            }
+        }
+
+        None
+    }
+
+    // explicit stack of (state, remaining successors), the states on it form the current path;
+    // also returns whether a state at the depth limit had successors left unexplored
+    fn depth_first<G: Fn(&S) -> bool>(&self, start: S, is_goal: &G, depth_limit: Option<usize>) -> (Option<Vec<S>>, bool) {
+        let mut visited = HashSet::from([start.clone()]);
+        let mut stack: Vec<(S, I::IntoIter)> = Vec::new();
+        let mut cut_off = false;
+        let mut entering = Some(start);
+
+        loop {
+            if let Some(state) = entering.take() {
+                if is_goal(&state) {
+                    let mut path = stack.into_iter().map(|(state, _)| state).collect::<Vec<_>>();
+                    path.push(state);
+                    return (Some(path), cut_off);
+                }
+
+                if depth_limit.is_some_and(|limit| stack.len() >= limit) {
+                    cut_off |= (self.successors)(&state).into_iter().any(|next| !visited.contains(&next));
+                    visited.remove(&state);
+                } else {
+                    let successors = (self.successors)(&state).into_iter();
+                    stack.push((state, successors));
+                }
+                continue;
+            }
+
+            let Some((_, successors)) = stack.last_mut() else {
+                return (None, cut_off);
+            };
+            match successors.find(|next| !visited.contains(next)) {
+                Some(next) => {
+                    visited.insert(next.clone());
+                    entering = Some(next);
+                }
+                None => {
+                    let (state, _) = stack.pop().unwrap();
+                    if depth_limit.is_some() {
+                        visited.remove(&state);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn build_path<S: Clone + Eq + Hash>(parents: &HashMap<S, Option<S>>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+    while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 and a shortcut 0 -> 10 -> 4, plus a dead end 1 -> 5
+    fn successors(state: &u32) -> Vec<u32> {
+        match state {
+            0 => vec![1, 10],
+            1 => vec![5, 2],
+            2 => vec![3],
+            3 => vec![4],
+            10 => vec![4],
+            _ => vec![],
+        }
+    }
+
+    fn steps_on_line(state: &i32) -> [i32; 2] {
+        [state - 1, state + 1]
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = Search::new(successors);
+
+        assert_eq!(Some(vec![0, 10, 4]), search.bfs(0, |state| *state == 4));
+        assert_eq!(None, search.bfs(0, |state| *state == 42));
+        assert_eq!(None, search.with_depth_limit(1).bfs(0, |state| *state == 4));
+    }
+
+    #[test]
+    fn test_dfs() {
+        let search = Search::new(successors);
+
+        assert_eq!(Some(vec![0, 1, 2, 3, 4]), search.dfs(0, |state| *state == 4));
+        assert_eq!(Some(vec![0, 10, 4]), Search::new(successors).with_depth_limit(2).dfs(0, |state| *state == 4));
+    }
+
+    #[test]
+    fn test_dfs_cycles() {
+        let search = Search::new(steps_on_line).with_depth_limit(5);
+
+        assert_eq!(Some(vec![0, -1, -2, -3]), search.dfs(0, |state| *state == -3));
+        assert_eq!(None, search.dfs(0, |state| *state == 6));
+    }
+
+    #[test]
+    fn test_iterative_deepening() {
+        let search = Search::new(steps_on_line).with_depth_limit(10);
+
+        assert_eq!(Some(vec![0, 1, 2, 3]), search.iterative_deepening(0, |state| *state == 3));
+        assert_eq!(None, search.iterative_deepening(0, |state| *state == 11));
+    }
+
+    #[test]
+    fn test_iterative_deepening_without_limit() {
+        let chain = Search::new(|state: &u32| if *state < 100 { vec![state + 1] } else { vec![] });
+        let ring = Search::new(|state: &u32| [(state + 1) % 10, (state + 9) % 10]);
+
+        assert_eq!(Some((0..=5).collect()), chain.iterative_deepening(0, |state| *state == 5));
+        assert_eq!(None, chain.iterative_deepening(0, |state| *state == 1000));
+        assert_eq!(None, ring.iterative_deepening(0, |state| *state == 10));
+        assert_eq!(None, Search::new(successors).iterative_deepening(0, |state| *state == 42));
+    }
+
+    #[test]
+    fn test_dfs_deep_chain() {
+        let search = Search::new(|state: &u32| if *state < 50_000 { vec![state + 1] } else { vec![] });
+
+        assert_eq!(Some(50_001), search.dfs(0, |state| *state == 50_000).map(|path| path.len()));
+    }
+
+    #[test]
+    fn test_beam() {
+        let search = Search::new(steps_on_line).with_depth_limit(20);
+
+        let path = search.beam(0, |state| *state == 7, 1, |state| (7 - state).abs());
+
+        assert_eq!(Some((0..=7).collect()), path);
+        assert_eq!(None, search.beam(0, |state| *state == 7, 1, |state| *state));
+    }
+
+    #[test]
+    fn test_best_first() {
+        let search = Search::new(|&(x, y): &(i32, i32)| [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)]);
+
+        let path = search.best_first((0, 0), |state| *state == (3, 2), |(x, y)| (3 - x).abs() + (2 - y).abs()).unwrap();
+
+        assert_eq!(6, path.len());
+        assert_eq!(Some(&(3, 2)), path.last());
+    }
+}